@@ -0,0 +1,821 @@
+use crate::input::Input;
+use crate::{KeyCombo, KeySequence, MatchMode};
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Identifies a binding registered in a [`KeybindManager`](crate::KeybindManager).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BindingId(usize);
+
+impl BindingId {
+    fn next() -> BindingId {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        BindingId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// When a binding triggers relative to its keys being pressed and released.
+///
+/// For a [`KeySequence`] the phase applies to its last combo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Phase {
+    /// Triggers as soon as the keys are pressed.
+    #[default]
+    Press,
+    /// Triggers once the pressed keys stop matching, e.g. to end push-to-talk.
+    Release,
+    /// Triggers once the keys are released, unless another key or mouse button was pressed while holding them. Useful
+    /// to open a launcher by tapping a modifier that is also used in other keybinds.
+    Tap,
+    /// Triggers once the keys have been held down for `after`, then every `repeat` while they are still held.
+    Hold {
+        /// How long the keys have to be held before triggering.
+        after: Duration,
+        /// How often to trigger again while the keys stay held, `None` to trigger only once. Repeats missed because
+        /// polling stalled are skipped.
+        repeat: Option<Duration>,
+    },
+    /// Triggers when the keys are pressed `count` times, each press following the previous one within `window`,
+    /// e.g. double-tapping Shift. Pressing any other key in between starts over.
+    Taps {
+        /// How many presses trigger the binding, a `count` of `0` triggers on every press like `1`.
+        count: u32,
+        /// How long to wait for the next press before starting over.
+        window: Duration,
+        /// Waits until `window` passed without another press before triggering, so a binding for more presses of
+        /// the same keys can coexist, e.g. a deferred single tap next to a double tap.
+        defer: bool,
+    },
+}
+
+/// Where the keys of a binding are in their press and release lifecycle after a poll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyState {
+    Pressed,
+    Held(Duration),
+    Released,
+}
+
+/// The outcome of feeding a poll to a [`Binding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Update {
+    pub(crate) triggered: bool,
+    pub(crate) state: Option<KeyState>,
+    /// How many times the binding triggered before while its keys stayed pressed, e.g. by [`Phase::Hold`] repeats.
+    pub(crate) repeat: u32,
+}
+
+/// Trigger detection for a single key sequence, fed with the pressed keys of every poll.
+#[derive(Debug, Clone)]
+pub(crate) struct Binding {
+    id: BindingId,
+    sequence: KeySequence,
+    phase: Phase,
+    mode: MatchMode,
+    pressed: Vec<Input>,
+    buttons: Vec<Input>,
+    clicked_while_held: bool,
+    step: usize,
+    step_pressed_at: Option<Instant>,
+    held_since: Option<Instant>,
+    next_hold: Option<Instant>,
+    triggers: u32,
+    taps: u32,
+    last_tap_at: Option<Instant>,
+}
+
+impl Binding {
+    pub(crate) fn new(sequence: KeySequence) -> Binding {
+        Binding {
+            id: BindingId::next(),
+            sequence,
+            phase: Phase::Press,
+            mode: MatchMode::Exact,
+            pressed: Vec::new(),
+            buttons: Vec::new(),
+            clicked_while_held: false,
+            step: 0,
+            step_pressed_at: None,
+            held_since: None,
+            next_hold: None,
+            triggers: 0,
+            taps: 0,
+            last_tap_at: None,
+        }
+    }
+
+    pub(crate) fn id(&self) -> BindingId {
+        self.id
+    }
+
+    pub(crate) fn sequence(&self) -> &KeySequence {
+        &self.sequence
+    }
+
+    pub(crate) fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    pub(crate) fn set_match_mode(&mut self, mode: MatchMode) {
+        self.mode = mode;
+    }
+
+    /// Records the keys and buttons pressed in this poll, returns whether that triggered the binding and the state of
+    /// its keys.
+    pub(crate) fn update(&mut self, pressed: &[Input], now: Instant) -> Update {
+        let uses_mouse = self.sequence.combos().iter().any(KeyCombo::uses_mouse);
+        let buttons: Vec<Input> = pressed.iter().filter(|input| matches!(input, Input::Mouse(_))).cloned().collect();
+        let pressed: Vec<Input> =
+            pressed.iter().filter(|input| uses_mouse || matches!(input, Input::Key(_))).cloned().collect();
+
+        // Clicks do not get in the way of matching keyboard combos, but a tap is about pressing nothing else.
+        let clicked = buttons.iter().any(|button| !self.buttons.contains(button));
+        self.buttons = buttons;
+        if clicked && self.held_since.is_some() {
+            self.clicked_while_held = true;
+        }
+
+        let held_since = self.held_since;
+        let triggered = self.trigger(&pressed, now);
+
+        let state = match (held_since, self.held_since) {
+            (None, Some(_)) => Some(KeyState::Pressed),
+            (Some(_), None) => Some(KeyState::Released),
+            (Some(since), Some(_)) => Some(KeyState::Held(now.duration_since(since))),
+            (None, None) => None,
+        };
+
+        let repeat = self.triggers;
+        if triggered {
+            self.triggers += 1;
+        }
+
+        Update { triggered, state, repeat }
+    }
+
+    fn trigger(&mut self, pressed: &[Input], now: Instant) -> bool {
+        let previous_pressed = mem::replace(&mut self.pressed, pressed.to_vec());
+        let changed = !same_inputs(&previous_pressed, pressed);
+
+        if self.held_since.is_some() && changed && !self.last_combo_matches(pressed) {
+            self.held_since = None;
+            self.next_hold = None;
+
+            match self.phase {
+                Phase::Release => return true,
+                Phase::Tap => {
+                    return !self.clicked_while_held && pressed.iter().all(|input| previous_pressed.contains(input))
+                }
+                Phase::Press | Phase::Hold { .. } | Phase::Taps { .. } => {}
+            }
+        }
+
+        if changed && !self.covers_new_keys(&previous_pressed) {
+            self.taps = 0;
+            self.last_tap_at = None;
+        }
+
+        if self.advance(&previous_pressed, now) {
+            // Matching again by releasing an extra key is not a new press of the keys, so it can not be released
+            // or held either.
+            if !pressed.iter().all(|input| previous_pressed.contains(input)) {
+                self.held_since = Some(now);
+                self.triggers = 0;
+                self.clicked_while_held = false;
+
+                match self.phase {
+                    // A hold too long to be represented as an instant never triggers.
+                    Phase::Hold { after, .. } => self.next_hold = now.checked_add(after),
+                    Phase::Taps { .. } => {
+                        if self.tap(now) {
+                            return true;
+                        }
+                    }
+                    Phase::Press | Phase::Release | Phase::Tap => {}
+                }
+            }
+
+            if self.phase == Phase::Press {
+                return true;
+            }
+        }
+
+        match self.phase {
+            Phase::Hold { .. } => self.hold(now),
+            Phase::Taps { .. } => self.taps_elapsed(now),
+            Phase::Press | Phase::Release | Phase::Tap => false,
+        }
+    }
+
+    /// Counts a press of a [`Phase::Taps`] binding, returns bool if it triggers right away.
+    fn tap(&mut self, now: Instant) -> bool {
+        let (count, window, defer) = match self.phase {
+            Phase::Taps { count, window, defer } => (count.max(1), window, defer),
+            _ => return false,
+        };
+
+        match self.last_tap_at {
+            Some(at) if now.duration_since(at) <= window => self.taps += 1,
+            _ => self.taps = 1,
+        }
+
+        self.last_tap_at = Some(now);
+
+        if defer || self.taps != count {
+            return false;
+        }
+
+        self.taps = 0;
+        self.last_tap_at = None;
+
+        true
+    }
+
+    /// Starts over once the window of a [`Phase::Taps`] binding passed, returns bool if a deferred binding triggers.
+    fn taps_elapsed(&mut self, now: Instant) -> bool {
+        let (count, window, defer) = match self.phase {
+            Phase::Taps { count, window, defer } => (count.max(1), window, defer),
+            _ => return false,
+        };
+
+        match self.last_tap_at {
+            Some(at) if now.duration_since(at) > window => {
+                let triggered = defer && self.taps == count;
+                self.taps = 0;
+                self.last_tap_at = None;
+
+                triggered
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns bool if every key pressed since the previous poll is part of the last combo or allowed on top of it.
+    fn covers_new_keys(&self, previous_pressed: &[Input]) -> bool {
+        let combo = match self.sequence.combos().last() {
+            Some(combo) => combo,
+            None => return false,
+        };
+
+        self.pressed
+            .iter()
+            .filter(|input| !previous_pressed.contains(input))
+            .all(|input| combo.covers(input) || self.mode.allows_extra(input))
+    }
+
+    /// Returns bool if the keys have been held long enough to trigger a [`Phase::Hold`] binding.
+    fn hold(&mut self, now: Instant) -> bool {
+        let repeat = match self.phase {
+            Phase::Hold { repeat, .. } => repeat,
+            _ => return false,
+        };
+
+        match self.next_hold {
+            Some(next_hold) if now >= next_hold => {
+                self.next_hold = repeat.and_then(|repeat| match next_hold.checked_add(repeat) {
+                    Some(next) if next > now => Some(next),
+                    // Skips the repeats missed while polling stalled, e.g. behind a slow callback, instead of
+                    // triggering on every poll until caught up.
+                    _ => now.checked_add(repeat),
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn last_combo_matches(&self, pressed: &[Input]) -> bool {
+        self.sequence.combos().last().is_some_and(|combo| combo.matches_inputs(pressed, self.mode))
+    }
+
+    /// Moves through the steps of the sequence, returns bool once its last combo got pressed.
+    fn advance(&mut self, previous_pressed: &[Input], now: Instant) -> bool {
+        let pressed = &self.pressed;
+
+        if self.step_pressed_at.is_some_and(|at| now.duration_since(at) > self.sequence.timeout()) {
+            self.step = 0;
+            self.step_pressed_at = None;
+        }
+
+        if same_inputs(previous_pressed, pressed) {
+            return false;
+        }
+
+        if self.step > 0 && !self.sequence.combos()[self.step].matches_inputs(pressed, self.mode) {
+            // Releasing keys between two steps is fine, only a newly pressed key breaks the sequence.
+            if pressed.iter().all(|input| previous_pressed.contains(input)) {
+                return false;
+            }
+
+            self.step = 0;
+            self.step_pressed_at = None;
+        }
+
+        // Keys that keep matching, e.g. because an extra key got pressed in a lenient mode, are not a new press.
+        let matches = |inputs: &[Input]| {
+            self.sequence.combos().get(self.step).is_some_and(|combo| combo.matches_inputs(inputs, self.mode))
+        };
+
+        if !matches(pressed) || matches(previous_pressed) {
+            return false;
+        }
+
+        self.step += 1;
+        self.step_pressed_at = Some(now);
+
+        if self.step < self.sequence.combos().len() {
+            return false;
+        }
+
+        self.step = 0;
+        self.step_pressed_at = None;
+
+        true
+    }
+}
+
+/// Compares both input lists as sets, as the OS does not report pressed keys in any particular order.
+fn same_inputs(left: &[Input], right: &[Input]) -> bool {
+    left.iter().all(|input| right.contains(input)) && right.iter().all(|input| left.contains(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MouseButton;
+    use device_query::Keycode;
+
+    struct Timeline {
+        binding: Binding,
+        now: Instant,
+    }
+
+    impl Timeline {
+        fn new(sequence: &str) -> Timeline {
+            Timeline {
+                binding: Binding::new(sequence.parse().unwrap()),
+                now: Instant::now(),
+            }
+        }
+
+        fn with_phase(sequence: &str, phase: Phase) -> Timeline {
+            let mut timeline = Timeline::new(sequence);
+            timeline.binding.set_phase(phase);
+
+            timeline
+        }
+
+        fn poll(&mut self, after: u64, pressed_keys: &[Keycode]) -> bool {
+            self.update(after, pressed_keys).triggered
+        }
+
+        fn state(&mut self, after: u64, pressed_keys: &[Keycode]) -> Option<KeyState> {
+            self.update(after, pressed_keys).state
+        }
+
+        fn update(&mut self, after: u64, pressed_keys: &[Keycode]) -> Update {
+            self.now += Duration::from_millis(after);
+
+            self.binding.update(&Input::collect(pressed_keys, &[]), self.now)
+        }
+    }
+
+    #[test]
+    fn sequence_triggers_on_last_step() {
+        let mut timeline = Timeline::new("Ctrl+X Ctrl+S");
+
+        assert!(!timeline.poll(10, &[Keycode::LControl]));
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::X]));
+        assert!(!timeline.poll(10, &[Keycode::LControl]));
+        assert!(timeline.poll(10, &[Keycode::LControl, Keycode::S]));
+    }
+
+    #[test]
+    fn sequence_allows_releasing_everything_between_steps() {
+        let mut timeline = Timeline::new("Ctrl+X S");
+
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::X]));
+        assert!(!timeline.poll(10, &[]));
+        assert!(timeline.poll(10, &[Keycode::S]));
+    }
+
+    #[test]
+    fn sequence_resets_on_wrong_key() {
+        let mut timeline = Timeline::new("Ctrl+X Ctrl+S");
+
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::X]));
+        assert!(!timeline.poll(10, &[Keycode::LControl]));
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::A]));
+        assert!(!timeline.poll(10, &[Keycode::LControl]));
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::S]));
+    }
+
+    #[test]
+    fn sequence_restarts_when_wrong_key_is_first_step() {
+        let mut timeline = Timeline::new("Ctrl+X Ctrl+S");
+
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::X]));
+        assert!(!timeline.poll(10, &[Keycode::LControl]));
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::X]));
+        assert!(!timeline.poll(10, &[Keycode::LControl]));
+        assert!(timeline.poll(10, &[Keycode::LControl, Keycode::S]));
+    }
+
+    #[test]
+    fn sequence_resets_on_timeout() {
+        let mut timeline = Timeline::new("Ctrl+X Ctrl+S");
+
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::X]));
+        assert!(!timeline.poll(10, &[Keycode::LControl]));
+        assert!(!timeline.poll(1500, &[Keycode::LControl, Keycode::S]));
+    }
+
+    #[test]
+    fn sequence_triggers_again_after_completion() {
+        let mut timeline = Timeline::new("A B");
+
+        assert!(!timeline.poll(10, &[Keycode::A]));
+        assert!(timeline.poll(10, &[Keycode::B]));
+        assert!(!timeline.poll(10, &[Keycode::A]));
+        assert!(timeline.poll(10, &[Keycode::B]));
+    }
+
+    #[test]
+    fn release_triggers_when_keys_stop_matching() {
+        let mut timeline = Timeline::with_phase("Ctrl+Space", Phase::Release);
+
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::Space]));
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::Space]));
+        assert!(timeline.poll(10, &[Keycode::LControl]));
+        assert!(!timeline.poll(10, &[]));
+    }
+
+    #[test]
+    fn release_triggers_when_extra_key_is_pressed() {
+        let mut timeline = Timeline::with_phase("Ctrl+Space", Phase::Release);
+
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::Space]));
+        assert!(timeline.poll(10, &[Keycode::LControl, Keycode::Space, Keycode::A]));
+    }
+
+    #[test]
+    fn release_applies_to_last_step_of_sequence() {
+        let mut timeline = Timeline::with_phase("A B", Phase::Release);
+
+        assert!(!timeline.poll(10, &[Keycode::A]));
+        assert!(!timeline.poll(10, &[]));
+        assert!(!timeline.poll(10, &[Keycode::B]));
+        assert!(timeline.poll(10, &[]));
+    }
+
+    #[test]
+    fn tap_does_not_trigger_when_other_key_was_held_first() {
+        let mut timeline = Timeline::with_phase("LAlt", Phase::Tap);
+
+        assert!(!timeline.poll(10, &[Keycode::A]));
+        assert!(!timeline.poll(10, &[Keycode::A, Keycode::LAlt]));
+        assert!(!timeline.poll(10, &[Keycode::LAlt]));
+        assert!(!timeline.poll(10, &[]));
+    }
+
+    #[test]
+    fn tap_triggers_on_clean_release() {
+        let mut timeline = Timeline::with_phase("LAlt", Phase::Tap);
+
+        assert!(!timeline.poll(10, &[Keycode::LAlt]));
+        assert!(timeline.poll(10, &[]));
+    }
+
+    #[test]
+    fn tap_does_not_trigger_when_another_key_was_pressed() {
+        let mut timeline = Timeline::with_phase("LAlt", Phase::Tap);
+
+        assert!(!timeline.poll(10, &[Keycode::LAlt]));
+        assert!(!timeline.poll(10, &[Keycode::LAlt, Keycode::F4]));
+        assert!(!timeline.poll(10, &[Keycode::LAlt]));
+        assert!(!timeline.poll(10, &[]));
+    }
+
+    fn hold(after: u64, repeat: Option<u64>) -> Phase {
+        Phase::Hold {
+            after: Duration::from_millis(after),
+            repeat: repeat.map(Duration::from_millis),
+        }
+    }
+
+    #[test]
+    fn hold_triggers_once_held_long_enough() {
+        let mut timeline = Timeline::with_phase("Esc", hold(800, None));
+
+        assert!(!timeline.poll(0, &[Keycode::Escape]));
+        assert!(!timeline.poll(400, &[Keycode::Escape]));
+        assert!(!timeline.poll(399, &[Keycode::Escape]));
+        assert!(timeline.poll(1, &[Keycode::Escape]));
+        assert!(!timeline.poll(1000, &[Keycode::Escape]));
+    }
+
+    #[test]
+    fn hold_does_not_trigger_when_released_early() {
+        let mut timeline = Timeline::with_phase("Esc", hold(800, None));
+
+        assert!(!timeline.poll(0, &[Keycode::Escape]));
+        assert!(!timeline.poll(500, &[]));
+        assert!(!timeline.poll(500, &[]));
+        assert!(!timeline.poll(0, &[Keycode::Escape]));
+        assert!(!timeline.poll(500, &[Keycode::Escape]));
+        assert!(timeline.poll(300, &[Keycode::Escape]));
+    }
+
+    #[test]
+    fn hold_repeats_while_held() {
+        let mut timeline = Timeline::with_phase("Esc", hold(500, Some(100)));
+
+        assert!(!timeline.poll(0, &[Keycode::Escape]));
+        assert!(timeline.poll(500, &[Keycode::Escape]));
+        assert!(!timeline.poll(50, &[Keycode::Escape]));
+        assert!(timeline.poll(50, &[Keycode::Escape]));
+        assert!(timeline.poll(100, &[Keycode::Escape]));
+        assert!(!timeline.poll(10, &[]));
+        assert!(!timeline.poll(500, &[]));
+    }
+
+    #[test]
+    fn hold_skips_repeats_missed_while_polling_stalled() {
+        let mut timeline = Timeline::with_phase("Esc", hold(100, Some(100)));
+
+        assert!(!timeline.poll(0, &[Keycode::Escape]));
+        assert!(timeline.poll(100, &[Keycode::Escape]));
+        assert!(timeline.poll(1000, &[Keycode::Escape]));
+        assert!(!timeline.poll(10, &[Keycode::Escape]));
+        assert!(!timeline.poll(10, &[Keycode::Escape]));
+        assert!(timeline.poll(80, &[Keycode::Escape]));
+    }
+
+    #[test]
+    fn hold_longer_than_an_instant_never_triggers() {
+        let mut binding = Binding::new("Esc".parse().unwrap());
+        let now = Instant::now();
+
+        binding.set_phase(Phase::Hold { after: Duration::MAX, repeat: None });
+
+        assert!(!binding.update(&Input::collect(&[Keycode::Escape], &[]), now).triggered);
+        assert!(!binding.update(&Input::collect(&[Keycode::Escape], &[]), now + Duration::from_secs(60)).triggered);
+    }
+
+    #[test]
+    fn hold_repeating_longer_than_an_instant_triggers_once() {
+        let phase = Phase::Hold {
+            after: Duration::ZERO,
+            repeat: Some(Duration::MAX),
+        };
+        let mut timeline = Timeline::with_phase("Esc", phase);
+
+        assert!(timeline.poll(0, &[Keycode::Escape]));
+        assert!(!timeline.poll(1000, &[Keycode::Escape]));
+    }
+
+    #[test]
+    fn hold_counts_repeats_until_released() {
+        let mut timeline = Timeline::with_phase("Esc", hold(100, Some(100)));
+
+        assert!(!timeline.poll(0, &[Keycode::Escape]));
+        assert_eq!(timeline.update(100, &[Keycode::Escape]).repeat, 0);
+        assert_eq!(timeline.update(100, &[Keycode::Escape]).repeat, 1);
+        assert_eq!(timeline.update(100, &[Keycode::Escape]).repeat, 2);
+        assert!(!timeline.poll(10, &[]));
+        assert!(!timeline.poll(10, &[Keycode::Escape]));
+        assert_eq!(timeline.update(100, &[Keycode::Escape]).repeat, 0);
+    }
+
+    #[test]
+    fn hold_is_interrupted_by_extra_key() {
+        let mut timeline = Timeline::with_phase("Esc", hold(500, None));
+
+        assert!(!timeline.poll(0, &[Keycode::Escape]));
+        assert!(!timeline.poll(200, &[Keycode::Escape, Keycode::A]));
+        assert!(!timeline.poll(200, &[Keycode::Escape]));
+        assert!(!timeline.poll(500, &[Keycode::Escape]));
+    }
+
+    fn taps(count: u32, defer: bool) -> Phase {
+        Phase::Taps {
+            count,
+            window: Duration::from_millis(300),
+            defer,
+        }
+    }
+
+    #[test]
+    fn taps_trigger_on_last_press_within_window() {
+        let mut timeline = Timeline::with_phase("LShift", taps(2, false));
+
+        assert!(!timeline.poll(0, &[Keycode::LShift]));
+        assert!(!timeline.poll(100, &[]));
+        assert!(timeline.poll(100, &[Keycode::LShift]));
+        assert!(!timeline.poll(100, &[]));
+        assert!(!timeline.poll(100, &[Keycode::LShift]));
+    }
+
+    #[test]
+    fn taps_start_over_when_window_passes() {
+        let mut timeline = Timeline::with_phase("LShift", taps(2, false));
+
+        assert!(!timeline.poll(0, &[Keycode::LShift]));
+        assert!(!timeline.poll(100, &[]));
+        assert!(!timeline.poll(400, &[Keycode::LShift]));
+        assert!(!timeline.poll(100, &[]));
+        assert!(timeline.poll(100, &[Keycode::LShift]));
+    }
+
+    #[test]
+    fn taps_start_over_when_other_key_is_pressed() {
+        let mut timeline = Timeline::with_phase("LShift", taps(2, false));
+
+        assert!(!timeline.poll(0, &[Keycode::LShift]));
+        assert!(!timeline.poll(50, &[]));
+        assert!(!timeline.poll(50, &[Keycode::A]));
+        assert!(!timeline.poll(50, &[]));
+        assert!(!timeline.poll(50, &[Keycode::LShift]));
+    }
+
+    #[test]
+    fn taps_count_presses_of_multi_key_combo() {
+        let mut timeline = Timeline::with_phase("Ctrl+G", taps(3, false));
+
+        assert!(!timeline.poll(0, &[Keycode::LControl]));
+        assert!(!timeline.poll(50, &[Keycode::LControl, Keycode::G]));
+        assert!(!timeline.poll(50, &[Keycode::LControl]));
+        assert!(!timeline.poll(50, &[Keycode::LControl, Keycode::G]));
+        assert!(!timeline.poll(50, &[]));
+        assert!(!timeline.poll(50, &[Keycode::G]));
+        assert!(timeline.poll(50, &[Keycode::G, Keycode::RControl]));
+    }
+
+    #[test]
+    fn zero_taps_trigger_like_a_single_tap() {
+        let mut timeline = Timeline::with_phase("LShift", taps(0, false));
+
+        assert!(timeline.poll(0, &[Keycode::LShift]));
+        assert!(!timeline.poll(100, &[]));
+        assert!(timeline.poll(100, &[Keycode::LShift]));
+    }
+
+    #[test]
+    fn deferred_taps_trigger_once_window_passes() {
+        let mut timeline = Timeline::with_phase("LShift", taps(1, true));
+
+        assert!(!timeline.poll(0, &[Keycode::LShift]));
+        assert!(!timeline.poll(100, &[]));
+        assert!(!timeline.poll(200, &[]));
+        assert!(timeline.poll(101, &[]));
+        assert!(!timeline.poll(500, &[]));
+    }
+
+    #[test]
+    fn deferred_taps_do_not_trigger_on_more_presses() {
+        let mut single = Timeline::with_phase("LShift", taps(1, true));
+        let mut double = Timeline::with_phase("LShift", taps(2, false));
+        let frames: [(u64, &[Keycode]); 5] = [
+            (0, &[Keycode::LShift]),
+            (100, &[]),
+            (100, &[Keycode::LShift]),
+            (100, &[]),
+            (500, &[]),
+        ];
+
+        let single_triggers: Vec<bool> = frames.iter().map(|(after, keys)| single.poll(*after, keys)).collect();
+        let double_triggers: Vec<bool> = frames.iter().map(|(after, keys)| double.poll(*after, keys)).collect();
+
+        assert_eq!(single_triggers, vec![false; 5]);
+        assert_eq!(double_triggers, vec![false, false, true, false, false]);
+    }
+
+    #[test]
+    fn reports_lifecycle_of_keys() {
+        let mut timeline = Timeline::new("Ctrl+G");
+
+        assert_eq!(timeline.state(0, &[Keycode::LControl]), None);
+        assert_eq!(timeline.state(10, &[Keycode::LControl, Keycode::G]), Some(KeyState::Pressed));
+        assert_eq!(
+            timeline.state(10, &[Keycode::LControl, Keycode::G]),
+            Some(KeyState::Held(Duration::from_millis(10)))
+        );
+        assert_eq!(
+            timeline.state(15, &[Keycode::G, Keycode::LControl]),
+            Some(KeyState::Held(Duration::from_millis(25)))
+        );
+        assert_eq!(timeline.state(10, &[Keycode::LControl]), Some(KeyState::Released));
+        assert_eq!(timeline.state(10, &[]), None);
+    }
+
+    #[test]
+    fn lifecycle_does_not_depend_on_phase() {
+        let mut timeline = Timeline::with_phase("Esc", Phase::Release);
+
+        assert_eq!(timeline.update(0, &[Keycode::Escape]), Update {
+            triggered: false,
+            state: Some(KeyState::Pressed),
+            repeat: 0,
+        });
+        assert_eq!(timeline.update(10, &[]), Update {
+            triggered: true,
+            state: Some(KeyState::Released),
+            repeat: 0,
+        });
+    }
+
+    fn with_mode(sequence: &str, mode: MatchMode) -> Timeline {
+        let mut timeline = Timeline::new(sequence);
+        timeline.binding.set_match_mode(mode);
+
+        timeline
+    }
+
+    #[test]
+    fn superset_triggers_with_extra_keys_held() {
+        let mut timeline = with_mode("Ctrl+G", MatchMode::Superset);
+
+        assert!(!timeline.poll(0, &[Keycode::W]));
+        assert!(!timeline.poll(10, &[Keycode::W, Keycode::LControl]));
+        assert!(timeline.poll(10, &[Keycode::W, Keycode::LControl, Keycode::G]));
+    }
+
+    #[test]
+    fn superset_does_not_trigger_again_on_extra_key() {
+        let mut timeline = with_mode("Ctrl+G", MatchMode::Superset);
+
+        assert!(timeline.poll(0, &[Keycode::LControl, Keycode::G]));
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::G, Keycode::W]));
+        assert!(!timeline.poll(10, &[Keycode::LControl, Keycode::G]));
+        assert_eq!(
+            timeline.state(10, &[Keycode::LControl, Keycode::G]),
+            Some(KeyState::Held(Duration::from_millis(30)))
+        );
+    }
+
+    #[test]
+    fn exact_modifiers_rejects_extra_modifier() {
+        let mut timeline = with_mode("Ctrl+G", MatchMode::ExactModifiersAnyOthers);
+
+        assert!(!timeline.poll(0, &[Keycode::LControl, Keycode::LShift, Keycode::G]));
+        assert!(timeline.poll(10, &[Keycode::LControl, Keycode::G]));
+        assert!(!timeline.poll(10, &[Keycode::LControl]));
+        assert!(timeline.poll(10, &[Keycode::LControl, Keycode::G, Keycode::W]));
+    }
+
+    #[test]
+    fn mouse_buttons_do_not_interrupt_keyboard_combo() {
+        let mut binding = Binding::new("Ctrl+G".parse().unwrap());
+        let now = Instant::now();
+
+        assert!(!binding.update(&Input::collect(&[], &[MouseButton::Left]), now).triggered);
+        assert!(binding.update(&Input::collect(&[Keycode::LControl, Keycode::G], &[MouseButton::Left]), now).triggered);
+    }
+
+    #[test]
+    fn click_while_held_cancels_tap() {
+        let mut binding = Binding::new("LAlt".parse().unwrap());
+        let now = Instant::now();
+        let polls: [(&[Keycode], &[MouseButton]); 5] = [
+            (&[Keycode::LAlt], &[]),
+            (&[Keycode::LAlt], &[MouseButton::Left]),
+            (&[Keycode::LAlt], &[MouseButton::Left]),
+            (&[Keycode::LAlt], &[]),
+            (&[], &[]),
+        ];
+
+        binding.set_phase(Phase::Tap);
+        let triggered: Vec<bool> =
+            polls.iter().map(|(keys, buttons)| binding.update(&Input::collect(keys, buttons), now).triggered).collect();
+
+        assert_eq!(triggered, vec![false; 5]);
+        assert!(!binding.update(&Input::collect(&[Keycode::LAlt], &[]), now).triggered);
+        assert!(binding.update(&Input::collect(&[], &[]), now).triggered);
+    }
+
+    #[test]
+    fn button_held_before_pressing_does_not_cancel_tap() {
+        let mut binding = Binding::new("LAlt".parse().unwrap());
+        let now = Instant::now();
+
+        binding.set_phase(Phase::Tap);
+
+        assert!(!binding.update(&Input::collect(&[], &[MouseButton::Left]), now).triggered);
+        assert!(!binding.update(&Input::collect(&[Keycode::LAlt], &[MouseButton::Left]), now).triggered);
+        assert!(!binding.update(&Input::collect(&[Keycode::LAlt], &[]), now).triggered);
+        assert!(binding.update(&Input::collect(&[], &[]), now).triggered);
+    }
+
+    #[test]
+    fn mouse_combo_triggers_on_button_press() {
+        let mut binding = Binding::new("Ctrl+MiddleClick".parse().unwrap());
+        let now = Instant::now();
+
+        assert!(!binding.update(&Input::collect(&[Keycode::LControl], &[]), now).triggered);
+        assert!(binding.update(&Input::collect(&[Keycode::LControl], &[MouseButton::Middle]), now).triggered);
+        assert!(!binding.update(&Input::collect(&[Keycode::LControl], &[MouseButton::Middle]), now).triggered);
+        assert_eq!(binding.update(&Input::collect(&[Keycode::LControl], &[]), now).state, Some(KeyState::Released));
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{BindingId, TriggerEvent};
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+pub(crate) type BoxError = Box<dyn Error + Send + Sync>;
+
+/// A callback following the keys of a binding, see [`Keybind::on_press`](crate::Keybind::on_press).
+pub(crate) type Callback = Box<dyn FnMut() -> Result<Flow, BoxError>>;
+
+/// A callback receiving how long the keys of a binding have been held.
+pub(crate) type HeldCallback = Box<dyn FnMut(Duration) -> Result<Flow, BoxError>>;
+
+/// A callback receiving the [`TriggerEvent`] of every trigger.
+pub(crate) type EventFn = dyn FnMut(&TriggerEvent) -> Result<Flow, BoxError>;
+
+/// A boxed [`EventFn`].
+pub(crate) type EventCallback = Box<EventFn>;
+
+/// A callback receiving the [`TriggerEvent`] of every trigger, that can be moved to a background thread.
+pub(crate) type SendEventCallback = Box<dyn FnMut(&TriggerEvent) -> Result<Flow, BoxError> + Send>;
+
+/// A callback receiving every [`CallbackError`].
+pub(crate) type ErrorCallback = Box<dyn FnMut(CallbackError)>;
+
+/// A callback receiving every [`CallbackError`] of a background thread.
+pub(crate) type SendErrorCallback = Box<dyn FnMut(CallbackError) + Send>;
+
+/// What the loop polling the keys does after a callback returned.
+///
+/// # Example
+///
+/// ```ignore
+/// use keybind::{Flow, KeybindManager, Keycode};
+///
+/// let mut manager = KeybindManager::new();
+///
+/// manager.register(&[Keycode::Escape], || Flow::Stop);
+/// manager.wait();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Flow {
+    /// Keeps polling and calling the other callbacks.
+    #[default]
+    Continue,
+    /// Makes [`wait`](crate::Keybind::wait) return, like the [`StopHandle`](crate::StopHandle) does.
+    Stop,
+    /// Handles the trigger on its own, so the callbacks after this one are skipped for it, e.g. the bindings
+    /// registered later in a [`KeybindManager`](crate::KeybindManager).
+    Consumed,
+}
+
+/// What a callback can return: nothing, a [`Flow`], or a `Result` of either whose error gets reported to the
+/// `on_error` hook.
+pub trait CallbackResult {
+    /// Returns how to go on after the callback, or its error.
+    fn into_result(self) -> Result<Flow, Box<dyn Error + Send + Sync>>;
+}
+
+impl CallbackResult for () {
+    fn into_result(self) -> Result<Flow, BoxError> {
+        Ok(Flow::Continue)
+    }
+}
+
+impl CallbackResult for Flow {
+    fn into_result(self) -> Result<Flow, BoxError> {
+        Ok(self)
+    }
+}
+
+impl<E: Into<BoxError>> CallbackResult for Result<(), E> {
+    fn into_result(self) -> Result<Flow, BoxError> {
+        self.map(|()| Flow::Continue).map_err(Into::into)
+    }
+}
+
+impl<E: Into<BoxError>> CallbackResult for Result<Flow, E> {
+    fn into_result(self) -> Result<Flow, BoxError> {
+        self.map_err(Into::into)
+    }
+}
+
+/// The reason a [`CallbackError`] occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallbackErrorKind {
+    /// The callback returned an error.
+    Failed,
+    /// The callback panicked.
+    Panicked,
+}
+
+/// Error passed to the `on_error` hook when a callback fails or panics.
+///
+/// # Example
+///
+/// ```ignore
+/// use keybind::{Keybind, Keycode};
+/// use std::process::Command;
+///
+/// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::T]);
+///
+/// keybind.on_trigger(|| Command::new("alacritty").spawn().map(|_| ()));
+/// keybind.on_error(|error| eprintln!("Could not open a terminal: {}", error));
+/// ```
+#[derive(Debug)]
+pub struct CallbackError {
+    kind: CallbackErrorKind,
+    binding: BindingId,
+    message: String,
+    source: Option<BoxError>,
+}
+
+impl CallbackError {
+    /// Returns the reason of the error.
+    pub fn kind(&self) -> CallbackErrorKind {
+        self.kind
+    }
+
+    /// Returns the id of the binding whose callback failed.
+    pub fn binding(&self) -> BindingId {
+        self.binding
+    }
+
+    /// Returns the message of the returned error or of the panic.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for CallbackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            CallbackErrorKind::Failed => write!(f, "callback failed: {}", self.message),
+            CallbackErrorKind::Panicked => write!(f, "callback panicked: {}", self.message),
+        }
+    }
+}
+
+impl Error for CallbackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|source| source.as_ref() as &(dyn Error + 'static))
+    }
+}
+
+/// Calls provided callback of a binding, turning a returned error or a panic into a [`CallbackError`].
+pub(crate) fn invoke<F>(binding: BindingId, callback: F) -> Result<Flow, CallbackError>
+where
+    F: FnOnce() -> Result<Flow, BoxError>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(callback)) {
+        Ok(Ok(flow)) => Ok(flow),
+        Ok(Err(error)) => Err(CallbackError {
+            kind: CallbackErrorKind::Failed,
+            binding,
+            message: error.to_string(),
+            source: Some(error),
+        }),
+        Err(payload) => Err(CallbackError {
+            kind: CallbackErrorKind::Panicked,
+            binding,
+            message: panic_message(payload.as_ref()),
+            source: None,
+        }),
+    }
+}
+
+/// Boxes a callback receiving the [`TriggerEvent`], whatever [`CallbackResult`] it returns.
+pub(crate) fn event_callback<C, R>(mut callback: C) -> EventCallback
+where
+    C: FnMut(&TriggerEvent) -> R + 'static,
+    R: CallbackResult,
+{
+    Box::new(move |event| callback(event).into_result())
+}
+
+/// The `on_error` hook used until another one is set, printing the error like an uncaught panic would.
+pub(crate) fn print_error(error: CallbackError) {
+    eprintln!("{}", error);
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (None, Some(message)) => message.clone(),
+        (None, None) => "unknown panic".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding::Binding;
+    use crate::Keycode;
+    use std::io;
+
+    fn binding() -> BindingId {
+        Binding::new((&[Keycode::A]).into()).id()
+    }
+
+    #[test]
+    fn reports_returned_error() {
+        let id = binding();
+        let error = invoke(id, || Err(io::Error::new(io::ErrorKind::Other, "no terminal").into())).unwrap_err();
+
+        assert_eq!(error.kind(), CallbackErrorKind::Failed);
+        assert_eq!(error.binding(), id);
+        assert_eq!(error.to_string(), "callback failed: no terminal");
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn catches_panic() {
+        let error = invoke(binding(), || panic!("out of {}", "cheese")).unwrap_err();
+
+        assert_eq!(error.kind(), CallbackErrorKind::Panicked);
+        assert_eq!(error.message(), "out of cheese");
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn passes_through_flow() {
+        assert_eq!(invoke(binding(), || ().into_result()).unwrap(), Flow::Continue);
+        assert_eq!(invoke(binding(), || Ok::<(), io::Error>(()).into_result()).unwrap(), Flow::Continue);
+        assert_eq!(invoke(binding(), || Flow::Stop.into_result()).unwrap(), Flow::Stop);
+        assert_eq!(invoke(binding(), || Ok::<_, io::Error>(Flow::Consumed).into_result()).unwrap(), Flow::Consumed);
+    }
+}
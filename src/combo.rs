@@ -0,0 +1,301 @@
+use crate::input::Input;
+use crate::MouseButton;
+use device_query::Keycode;
+
+/// A modifier key matched on either side of the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Modifier {
+    /// `LControl` or `RControl`.
+    Control,
+    /// `LShift` or `RShift`.
+    Shift,
+    /// `LAlt` or `RAlt`.
+    Alt,
+}
+
+impl Modifier {
+    /// Returns the left and right keys of the modifier.
+    pub fn keys(self) -> [Keycode; 2] {
+        match self {
+            Modifier::Control => [Keycode::LControl, Keycode::RControl],
+            Modifier::Shift => [Keycode::LShift, Keycode::RShift],
+            Modifier::Alt => [Keycode::LAlt, Keycode::RAlt],
+        }
+    }
+
+    /// Returns bool if the key is either side of the modifier.
+    pub fn matches(self, key: &Keycode) -> bool {
+        self.keys().contains(key)
+    }
+}
+
+/// How strictly the pressed keys have to match a [`KeyCombo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MatchMode {
+    /// No other key may be pressed.
+    #[default]
+    Exact,
+    /// Any other key may be pressed as well, e.g. for games where fingers rest on movement keys.
+    Superset,
+    /// Any other key may be pressed as well, except for modifiers, so `Ctrl+G` does not match `Ctrl+Shift+G`.
+    ExactModifiersAnyOthers,
+}
+
+impl MatchMode {
+    /// Returns bool if the key or button may be pressed on top of the combo.
+    pub(crate) fn allows_extra(self, input: &Input) -> bool {
+        match (self, input) {
+            (MatchMode::Exact, _) => false,
+            (MatchMode::Superset, _) => true,
+            (MatchMode::ExactModifiersAnyOthers, Input::Mouse(_)) => true,
+            (MatchMode::ExactModifiersAnyOthers, Input::Key(key)) => {
+                ![Modifier::Control, Modifier::Shift, Modifier::Alt].iter().any(|modifier| modifier.matches(key))
+            }
+        }
+    }
+}
+
+/// A single element of a [`KeyCombo`], either a specific key, any side of a modifier or a mouse button.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyMatcher {
+    /// Matches exactly this key, e.g. only `LControl`.
+    Key(Keycode),
+    /// Matches either side of the modifier, e.g. `LControl` or `RControl`.
+    Modifier(Modifier),
+    /// Matches this mouse button.
+    Mouse(MouseButton),
+}
+
+impl KeyMatcher {
+    /// Returns bool if the pressed key satisfies this matcher.
+    pub fn matches(&self, key: &Keycode) -> bool {
+        self.matches_input(&Input::Key(key.clone()))
+    }
+
+    pub(crate) fn matches_input(&self, input: &Input) -> bool {
+        match (self, input) {
+            (KeyMatcher::Key(expected), Input::Key(key)) => expected == key,
+            (KeyMatcher::Modifier(modifier), Input::Key(key)) => modifier.matches(key),
+            (KeyMatcher::Mouse(expected), Input::Mouse(button)) => expected == button,
+            _ => false,
+        }
+    }
+}
+
+impl From<Keycode> for KeyMatcher {
+    fn from(key: Keycode) -> KeyMatcher {
+        KeyMatcher::Key(key)
+    }
+}
+
+impl From<Modifier> for KeyMatcher {
+    fn from(modifier: Modifier) -> KeyMatcher {
+        KeyMatcher::Modifier(modifier)
+    }
+}
+
+impl From<MouseButton> for KeyMatcher {
+    fn from(button: MouseButton) -> KeyMatcher {
+        KeyMatcher::Mouse(button)
+    }
+}
+
+/// A set of keys that have to be pressed together.
+///
+/// The order of the keys does not matter. Besides plain [`Keycode`]s a combo can contain [`Modifier`]s, which are
+/// satisfied by either side of the keyboard, and [`MouseButton`]s. Mouse buttons are ignored by combos that do not
+/// contain any, so clicking does not get in the way of keyboard combos. Combos can also be parsed from strings such
+/// as `Ctrl+Shift+G`.
+///
+/// # Example
+///
+/// ```
+/// use keybind::{KeyCombo, KeyMatcher, Keycode, Modifier};
+///
+/// let combo = KeyCombo::new(&[KeyMatcher::from(Modifier::Control), Keycode::G.into()]);
+///
+/// assert!(combo.matches(&[Keycode::LControl, Keycode::G]));
+/// assert!(combo.matches(&[Keycode::G, Keycode::RControl]));
+/// assert!(!combo.matches(&[Keycode::G]));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KeyCombo {
+    matchers: Vec<KeyMatcher>,
+}
+
+impl KeyCombo {
+    /// Constructs a new `KeyCombo` from keys, modifiers or a mix of both.
+    pub fn new<K: Into<KeyMatcher> + Clone>(keys: &[K]) -> KeyCombo {
+        KeyCombo {
+            matchers: keys.iter().cloned().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the matchers the combo is made of, in the order they were provided.
+    pub fn matchers(&self) -> &[KeyMatcher] {
+        &self.matchers
+    }
+
+    /// Returns bool if the pressed keys are exactly this combo.
+    ///
+    /// Every matcher has to be satisfied by at least one pressed key and every pressed key has to satisfy at least
+    /// one matcher, so holding both `LControl` and `RControl` still matches a [`Modifier::Control`] combo.
+    pub fn matches(&self, pressed: &[Keycode]) -> bool {
+        self.matches_with(pressed, MatchMode::Exact)
+    }
+
+    /// Returns bool if the pressed keys match this combo in provided mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use keybind::{KeyCombo, Keycode, MatchMode};
+    ///
+    /// let combo = KeyCombo::new(&[Keycode::LControl, Keycode::G]);
+    ///
+    /// assert!(combo.matches_with(&[Keycode::LControl, Keycode::G, Keycode::W], MatchMode::Superset));
+    /// assert!(!combo.matches_with(&[Keycode::LControl, Keycode::G, Keycode::W], MatchMode::Exact));
+    /// ```
+    pub fn matches_with(&self, pressed: &[Keycode], mode: MatchMode) -> bool {
+        self.matches_inputs(&Input::collect(pressed, &[]), mode)
+    }
+
+    /// Returns bool if the pressed keys and buttons match this combo in provided mode.
+    pub(crate) fn matches_inputs(&self, pressed: &[Input], mode: MatchMode) -> bool {
+        let uses_mouse = self.uses_mouse();
+        let mut pressed = pressed.iter().filter(|input| uses_mouse || matches!(input, Input::Key(_)));
+
+        self.matchers.iter().all(|matcher| pressed.clone().any(|input| matcher.matches_input(input)))
+            && pressed.all(|input| self.covers(input) || mode.allows_extra(input))
+    }
+
+    /// Returns bool if the key or button satisfies any matcher of the combo.
+    pub(crate) fn covers(&self, input: &Input) -> bool {
+        self.matchers.iter().any(|matcher| matcher.matches_input(input))
+    }
+
+    /// Returns bool if any matcher is a mouse button.
+    pub(crate) fn uses_mouse(&self) -> bool {
+        self.matchers.iter().any(|matcher| matches!(matcher, KeyMatcher::Mouse(_)))
+    }
+}
+
+/// Combos are equal when they are made of the same matchers, in any order.
+impl PartialEq for KeyCombo {
+    fn eq(&self, other: &KeyCombo) -> bool {
+        self.matchers.iter().all(|matcher| other.matchers.contains(matcher))
+            && other.matchers.iter().all(|matcher| self.matchers.contains(matcher))
+    }
+}
+
+impl<K: Into<KeyMatcher> + Clone> From<&[K]> for KeyCombo {
+    fn from(keys: &[K]) -> KeyCombo {
+        KeyCombo::new(keys)
+    }
+}
+
+impl<K: Into<KeyMatcher> + Clone, const N: usize> From<&[K; N]> for KeyCombo {
+    fn from(keys: &[K; N]) -> KeyCombo {
+        KeyCombo::new(keys)
+    }
+}
+
+impl<K: Into<KeyMatcher> + Clone> From<&Vec<K>> for KeyCombo {
+    fn from(keys: &Vec<K>) -> KeyCombo {
+        KeyCombo::new(keys)
+    }
+}
+
+impl<K: Into<KeyMatcher>> From<Vec<K>> for KeyCombo {
+    fn from(keys: Vec<K>) -> KeyCombo {
+        KeyCombo {
+            matchers: keys.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctrl_g() -> KeyCombo {
+        KeyCombo::new(&[KeyMatcher::from(Modifier::Control), Keycode::G.into()])
+    }
+
+    #[test]
+    fn modifier_matches_either_side() {
+        assert!(ctrl_g().matches(&[Keycode::LControl, Keycode::G]));
+        assert!(ctrl_g().matches(&[Keycode::RControl, Keycode::G]));
+        assert!(ctrl_g().matches(&[Keycode::LControl, Keycode::RControl, Keycode::G]));
+    }
+
+    #[test]
+    fn modifier_does_not_match_other_modifiers() {
+        assert!(!ctrl_g().matches(&[Keycode::LShift, Keycode::G]));
+        assert!(!ctrl_g().matches(&[Keycode::LControl, Keycode::LAlt, Keycode::G]));
+    }
+
+    #[test]
+    fn sided_key_matches_only_its_side() {
+        let combo = KeyCombo::new(&[Keycode::LShift, Keycode::A]);
+
+        assert!(combo.matches(&[Keycode::A, Keycode::LShift]));
+        assert!(!combo.matches(&[Keycode::RShift, Keycode::A]));
+    }
+
+    #[test]
+    fn superset_allows_any_extra_key() {
+        let pressed = [Keycode::LControl, Keycode::LShift, Keycode::G, Keycode::W];
+
+        assert!(ctrl_g().matches_with(&pressed, MatchMode::Superset));
+        assert!(!ctrl_g().matches_with(&[Keycode::LShift, Keycode::G], MatchMode::Superset));
+    }
+
+    #[test]
+    fn exact_modifiers_allows_extra_keys_but_not_modifiers() {
+        let mode = MatchMode::ExactModifiersAnyOthers;
+
+        assert!(ctrl_g().matches_with(&[Keycode::LControl, Keycode::G, Keycode::W], mode));
+        assert!(ctrl_g().matches_with(&[Keycode::LControl, Keycode::RControl, Keycode::G], mode));
+        assert!(!ctrl_g().matches_with(&[Keycode::LControl, Keycode::LShift, Keycode::G], mode));
+    }
+
+    #[test]
+    fn mouse_buttons_are_ignored_without_mouse_matcher() {
+        let pressed = Input::collect(&[Keycode::LControl, Keycode::G], &[MouseButton::Left]);
+
+        assert!(ctrl_g().matches_inputs(&pressed, MatchMode::Exact));
+    }
+
+    #[test]
+    fn mouse_matcher_requires_button() {
+        let combo = KeyCombo::new(&[KeyMatcher::from(Modifier::Control), MouseButton::Middle.into()]);
+
+        assert!(combo.matches_inputs(&Input::collect(&[Keycode::RControl], &[MouseButton::Middle]), MatchMode::Exact));
+        assert!(!combo.matches_inputs(&Input::collect(&[Keycode::RControl], &[]), MatchMode::Exact));
+        assert!(!combo.matches(&[Keycode::RControl]));
+    }
+
+    #[test]
+    fn extra_buttons_count_for_mouse_combos() {
+        let combo = KeyCombo::new(&[MouseButton::Button4, MouseButton::Button5]);
+        let pressed = Input::collect(&[], &[MouseButton::Button4, MouseButton::Button5, MouseButton::Left]);
+
+        assert!(!combo.matches_inputs(&pressed, MatchMode::Exact));
+        assert!(combo.matches_inputs(&pressed, MatchMode::ExactModifiersAnyOthers));
+    }
+
+    #[test]
+    fn equality_ignores_order() {
+        assert_eq!(KeyCombo::new(&[Keycode::A, Keycode::B]), KeyCombo::new(&[Keycode::B, Keycode::A]));
+        assert_ne!(KeyCombo::new(&[Keycode::A, Keycode::B]), KeyCombo::new(&[Keycode::A]));
+    }
+
+    #[test]
+    fn every_matcher_has_to_be_pressed() {
+        let combo = KeyCombo::new(&[Modifier::Control, Modifier::Shift]);
+
+        assert!(combo.matches(&[Keycode::RShift, Keycode::LControl]));
+        assert!(!combo.matches(&[Keycode::LControl, Keycode::RControl]));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::binding::Binding;
+use crate::input::Snapshot;
+use crate::{BindingId, KeyCombo, Keycode, MouseButton};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// Identifies a listener added with [`add_listener`](crate::Keybind::add_listener), to remove it again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubscriptionId(usize);
+
+impl SubscriptionId {
+    pub(crate) fn next() -> SubscriptionId {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        SubscriptionId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Describes a trigger of a binding, passed to callbacks registered with
+/// [`on_trigger_event`](crate::Keybind::on_trigger_event) or
+/// [`register_event`](crate::KeybindManager::register_event).
+///
+/// # Example
+///
+/// ```ignore
+/// use keybind::{Keybind, Keycode, Phase};
+/// use std::time::Duration;
+///
+/// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::G]);
+///
+/// keybind.set_phase(Phase::Hold { after: Duration::from_millis(500), repeat: Some(Duration::from_millis(100)) });
+/// keybind.on_trigger_event(|event| {
+///     println!("{} triggered {} times at {:?}", event.combo(), event.repeat() + 1, event.mouse_position());
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerEvent {
+    binding: BindingId,
+    combo: KeyCombo,
+    keys: Vec<Keycode>,
+    buttons: Vec<MouseButton>,
+    time: Instant,
+    repeat: u32,
+    mouse_position: (i32, i32),
+}
+
+impl TriggerEvent {
+    pub(crate) fn new(binding: &Binding, snapshot: &Snapshot, time: Instant, repeat: u32) -> TriggerEvent {
+        TriggerEvent {
+            binding: binding.id(),
+            combo: binding.sequence().combos().last().cloned().unwrap_or_default(),
+            keys: snapshot.keys(),
+            buttons: snapshot.buttons(),
+            time,
+            repeat,
+            mouse_position: snapshot.position,
+        }
+    }
+
+    /// Returns the id of the binding that triggered.
+    pub fn binding(&self) -> BindingId {
+        self.binding
+    }
+
+    /// Returns the combo that triggered, the last step for a [`KeySequence`](crate::KeySequence).
+    pub fn combo(&self) -> &KeyCombo {
+        &self.combo
+    }
+
+    /// Returns every key pressed when the binding triggered, including keys that are not part of the combo.
+    pub fn keys(&self) -> &[Keycode] {
+        &self.keys
+    }
+
+    /// Returns every mouse button pressed when the binding triggered.
+    pub fn buttons(&self) -> &[MouseButton] {
+        &self.buttons
+    }
+
+    /// Returns when the poll that triggered the binding happened.
+    pub fn time(&self) -> Instant {
+        self.time
+    }
+
+    /// Returns how many times the binding triggered before while its keys stayed pressed, e.g. `1` for the first
+    /// repeat of a [`Phase::Hold`](crate::Phase::Hold) binding.
+    pub fn repeat(&self) -> u32 {
+        self.repeat
+    }
+
+    /// Returns the position of the mouse when the binding triggered.
+    pub fn mouse_position(&self) -> (i32, i32) {
+        self.mouse_position
+    }
+}
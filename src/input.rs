@@ -0,0 +1,178 @@
+use crate::KeySource;
+use device_query::{Keycode, MouseState};
+
+/// A mouse button that can be part of a [`KeyCombo`](crate::KeyCombo), e.g. `Ctrl+MiddleClick`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    /// The primary button, button 1.
+    Left,
+    /// The wheel button, see [`number`](MouseButton::number) for its index on each platform.
+    Middle,
+    /// The secondary button, see [`number`](MouseButton::number) for its index on each platform.
+    Right,
+    /// Button 4: the first side button, usually back, on Windows. On Linux X11 reports scrolling the wheel up as
+    /// button 4, as it has no side buttons in its button state.
+    Button4,
+    /// Button 5: the second side button, usually forward, on Windows. On Linux X11 reports scrolling the wheel down
+    /// as button 5.
+    Button5,
+}
+
+impl MouseButton {
+    const ALL: [MouseButton; 5] = [
+        MouseButton::Left,
+        MouseButton::Middle,
+        MouseButton::Right,
+        MouseButton::Button4,
+        MouseButton::Button5,
+    ];
+
+    /// Returns the 1-based index of the button in [`MouseState::button_pressed`] on the current platform.
+    ///
+    /// X11 numbers the middle button 2 and the right one 3, while on Windows the right button comes first at 2.
+    pub fn number(self) -> usize {
+        self.number_on(cfg!(windows))
+    }
+
+    /// Returns all buttons pressed in provided mouse state.
+    pub fn pressed(state: &MouseState) -> Vec<MouseButton> {
+        MouseButton::pressed_on(state, cfg!(windows))
+    }
+
+    fn number_on(self, windows: bool) -> usize {
+        match (self, windows) {
+            (MouseButton::Left, _) => 1,
+            (MouseButton::Middle, false) | (MouseButton::Right, true) => 2,
+            (MouseButton::Right, false) | (MouseButton::Middle, true) => 3,
+            (MouseButton::Button4, _) => 4,
+            (MouseButton::Button5, _) => 5,
+        }
+    }
+
+    fn pressed_on(state: &MouseState, windows: bool) -> Vec<MouseButton> {
+        MouseButton::ALL
+            .iter()
+            .copied()
+            .filter(|button| state.button_pressed.get(button.number_on(windows)).copied().unwrap_or(false))
+            .collect()
+    }
+}
+
+/// A key or mouse button held down while polling.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Input {
+    Key(Keycode),
+    Mouse(MouseButton),
+}
+
+/// Everything read from a [`KeySource`] in a single poll.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Snapshot {
+    pub(crate) inputs: Vec<Input>,
+    pub(crate) position: (i32, i32),
+}
+
+impl Snapshot {
+    /// Queries the source for pressed keys, pressed mouse buttons and the mouse position.
+    pub(crate) fn poll<S: KeySource + ?Sized>(source: &mut S) -> Snapshot {
+        let keys = source.get_keys();
+        let mouse = source.get_mouse();
+
+        Snapshot {
+            inputs: Input::collect(&keys, &MouseButton::pressed(&mouse)),
+            position: mouse.coords,
+        }
+    }
+
+    /// Returns the pressed keys, without mouse buttons.
+    pub(crate) fn keys(&self) -> Vec<Keycode> {
+        self.inputs
+            .iter()
+            .filter_map(|input| match input {
+                Input::Key(key) => Some(key.clone()),
+                Input::Mouse(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns the pressed mouse buttons.
+    pub(crate) fn buttons(&self) -> Vec<MouseButton> {
+        self.inputs
+            .iter()
+            .filter_map(|input| match input {
+                Input::Key(_) => None,
+                Input::Mouse(button) => Some(*button),
+            })
+            .collect()
+    }
+}
+
+impl Input {
+    pub(crate) fn collect(keys: &[Keycode], buttons: &[MouseButton]) -> Vec<Input> {
+        keys.iter()
+            .cloned()
+            .map(Input::Key)
+            .chain(buttons.iter().copied().map(Input::Mouse))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_pressed_buttons_from_mouse_state() {
+        let state = MouseState {
+            coords: (0, 0),
+            button_pressed: vec![false, true, false, true, false, true],
+        };
+
+        let pressed = MouseButton::pressed_on(&state, false);
+
+        assert_eq!(pressed, vec![MouseButton::Left, MouseButton::Right, MouseButton::Button5]);
+    }
+
+    #[test]
+    fn reads_x11_button_order() {
+        let state = MouseState {
+            coords: (0, 0),
+            button_pressed: vec![false, false, true, false, false, false],
+        };
+
+        assert_eq!(MouseButton::pressed_on(&state, false), vec![MouseButton::Middle]);
+    }
+
+    #[test]
+    fn reads_windows_button_order() {
+        let state = MouseState {
+            coords: (0, 0),
+            button_pressed: vec![false, false, true, false, false, false],
+        };
+
+        assert_eq!(MouseButton::pressed_on(&state, true), vec![MouseButton::Right]);
+    }
+
+    #[test]
+    fn numbers_buttons_for_current_platform() {
+        let mut button_pressed = vec![false; 6];
+        button_pressed[MouseButton::Right.number()] = true;
+        let state = MouseState {
+            coords: (0, 0),
+            button_pressed,
+        };
+
+        assert_eq!(MouseButton::Right.number(), if cfg!(windows) { 2 } else { 3 });
+        assert_eq!(MouseButton::pressed(&state), vec![MouseButton::Right]);
+    }
+
+    #[test]
+    fn ignores_missing_buttons() {
+        let state = MouseState {
+            coords: (0, 0),
+            button_pressed: vec![false, false, true],
+        };
+
+        assert_eq!(MouseButton::pressed_on(&state, false), vec![MouseButton::Middle]);
+    }
+}
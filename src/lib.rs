@@ -20,16 +20,65 @@
 //! ```
 //!
 
-use device_query::{DeviceQuery, DeviceState};
-use std::mem;
+mod binding;
+mod callback;
+mod combo;
+mod event;
+mod input;
+mod listener;
+mod manager;
+mod mock;
+mod notation;
+mod parse;
+mod poll;
+mod sequence;
+mod source;
+mod stop;
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "async")]
+mod task;
 
-pub use device_query::Keycode;
+use binding::{Binding, KeyState};
+use callback::{Callback, ErrorCallback, EventCallback, EventFn, HeldCallback, SendErrorCallback, SendEventCallback};
+use input::Snapshot;
+use std::iter;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
 
-pub struct Keybind {
-    device_state: DeviceState,
-    pressed_keys: Vec<Keycode>,
-    key_binds: Vec<Keycode>,
-    on_trigger: Box<Fn()>,
+pub use binding::{BindingId, Phase};
+pub use callback::{CallbackError, CallbackErrorKind, CallbackResult, Flow};
+pub use combo::{KeyCombo, KeyMatcher, MatchMode, Modifier};
+pub use device_query::{DeviceState, Keycode, MouseState};
+pub use event::{SubscriptionId, TriggerEvent};
+pub use input::MouseButton;
+pub use listener::ListenerHandle;
+pub use manager::KeybindManager;
+pub use mock::MockKeySource;
+pub use notation::{ComboDisplay, Notation};
+pub use parse::{ParseError, ParseErrorKind};
+pub use poll::PollInterval;
+pub use sequence::{KeySequence, SequenceDisplay};
+pub use source::KeySource;
+pub use stop::StopHandle;
+#[cfg(feature = "async")]
+pub use stream::TriggerStream;
+#[cfg(feature = "async")]
+pub use task::{Concurrency, HandlerFuture};
+
+pub struct Keybind<S = DeviceState> {
+    source: S,
+    binding: Binding,
+    on_trigger: EventCallback,
+    on_trigger_async: Option<SendEventCallback>,
+    listeners: Vec<(SubscriptionId, EventCallback)>,
+    on_press: Option<Callback>,
+    on_held: Option<HeldCallback>,
+    on_release: Option<Callback>,
+    on_error: ErrorCallback,
+    on_listener_error: SendErrorCallback,
+    poll_interval: PollInterval,
+    stop: StopHandle,
 }
 
 impl Keybind {
@@ -42,12 +91,147 @@ impl Keybind {
     ///
     /// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::G]);
     /// ```
-    pub fn new(keys: &[Keycode]) -> Keybind {
+    ///
+    /// Use [`Modifier`]s to accept either side of the keyboard:
+    ///
+    /// ```ignore
+    /// use keybind::{KeyMatcher, Keybind, Keycode, Modifier};
+    ///
+    /// let mut keybind = Keybind::new(&[KeyMatcher::from(Modifier::Control), Keycode::G.into()]);
+    /// ```
+    ///
+    /// Provide a [`KeySequence`] for combos that have to be pressed one after the other:
+    ///
+    /// ```ignore
+    /// use keybind::{KeySequence, Keybind};
+    ///
+    /// let mut keybind = Keybind::new("Ctrl+X Ctrl+S".parse::<KeySequence>().unwrap());
+    /// ```
+    pub fn new<C: Into<KeySequence>>(keys: C) -> Keybind {
+        Keybind::with_source(keys, DeviceState::new())
+    }
+
+    /// Constructs a new `Keybind` from a string such as `Ctrl+Shift+G`, see [`KeyCombo`] for the accepted key names.
+    /// Combos separated by spaces form a [`KeySequence`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::Keybind;
+    ///
+    /// let mut keybind = Keybind::parse("Ctrl+Shift+G").unwrap();
+    /// let mut save = Keybind::parse("Ctrl+X Ctrl+S").unwrap();
+    /// ```
+    pub fn parse(keys: &str) -> Result<Keybind, ParseError> {
+        Ok(Keybind::new(keys.parse::<KeySequence>()?))
+    }
+
+    /// Starts polling on a background thread, calling provided callback when the keybind is triggered.
+    ///
+    /// The thread opens its own connection to the OS, as [`DeviceState`] cannot be moved across threads. Callbacks
+    /// set with [`on_trigger`](Keybind::on_trigger), [`add_listener`](Keybind::add_listener),
+    /// [`on_press`](Keybind::on_press), [`on_held`](Keybind::on_held), [`on_release`](Keybind::on_release) or
+    /// [`on_error`](Keybind::on_error) are not carried over, as they are not required to be `Send`. Errors of provided
+    /// callback go to the hook set with [`on_listener_error`](Keybind::on_listener_error) instead. With the `async`
+    /// feature, the handler set with `on_trigger_async` is carried over and runs after provided callback.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    ///
+    ///fn main() {
+    ///    let listener = Keybind::new(&[Keycode::LControl, Keycode::G]).spawn(|| {
+    ///        println!("This will be printed when you press CTRL+G");
+    ///    });
+    ///
+    ///    // ...
+    ///
+    ///    listener.stop();
+    ///    listener.join().unwrap();
+    ///}
+    /// ```
+    pub fn spawn<C, R>(self, callback: C) -> ListenerHandle
+    where
+        C: FnMut() -> R + Send + 'static,
+        R: CallbackResult,
+    {
+        self.spawn_with(DeviceState::new, callback)
+    }
+
+    /// Starts polling on a background thread and returns a channel receiving a [`TriggerEvent`] for every trigger,
+    /// as an alternative to callbacks.
+    ///
+    /// The thread stops once the [`StopHandle`] returned by [`stop_handle`](Keybind::stop_handle) is used, or on
+    /// the first trigger after the receiver got dropped.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    ///
+    ///fn main() {
+    ///    let events = Keybind::new(&[Keycode::LControl, Keycode::G]).events();
+    ///
+    ///    for event in events {
+    ///        println!("{} pressed at {:?}", event.combo(), event.time());
+    ///    }
+    ///}
+    /// ```
+    pub fn events(self) -> Receiver<TriggerEvent> {
+        self.events_with(DeviceState::new)
+    }
+
+    /// Same as [`events`](Keybind::events), but returns an async [`TriggerStream`] to await triggers from an async
+    /// runtime, while the blocking polling stays on a background thread.
+    ///
+    /// The thread stops once the [`StopHandle`] returned by [`stop_handle`](Keybind::stop_handle) is used, or at the
+    /// next poll after the stream got dropped.
+    ///
+    /// Requires the `async` feature.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    ///
+    /// let mut triggers = Keybind::new(&[Keycode::LControl, Keycode::G]).stream();
+    ///
+    /// tokio::select! {
+    ///     Some(event) = triggers.next_trigger() => println!("{} pressed", event.combo()),
+    ///     response = client.get(url).send() => println!("{:?}", response),
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn stream(self) -> TriggerStream {
+        self.stream_with(DeviceState::new)
+    }
+}
+
+impl<S: KeySource> Keybind<S> {
+    /// Constructs a new `Keybind` that reads pressed keys from the provided source instead of the OS.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{DeviceState, Keybind, Keycode};
+    ///
+    /// let mut keybind = Keybind::with_source(&[Keycode::LControl, Keycode::G], DeviceState::new());
+    /// ```
+    pub fn with_source<C: Into<KeySequence>>(keys: C, source: S) -> Keybind<S> {
         Keybind {
-            device_state: DeviceState::new(),
-            pressed_keys: Vec::new(),
-            key_binds: keys.to_vec(),
-            on_trigger: Box::new(||{})
+            source,
+            binding: Binding::new(keys.into()),
+            on_trigger: Box::new(|_| Ok(Flow::Continue)),
+            on_trigger_async: None,
+            listeners: Vec::new(),
+            on_press: None,
+            on_held: None,
+            on_release: None,
+            on_error: Box::new(callback::print_error),
+            on_listener_error: Box::new(callback::print_error),
+            poll_interval: PollInterval::default(),
+            stop: StopHandle::default(),
         }
     }
 
@@ -67,17 +251,34 @@ impl Keybind {
     /// }
     /// ```
     pub fn triggered(&mut self) -> bool {
-        let previous_pressed_keys = mem::replace(
-            &mut self.pressed_keys,
-            self.device_state.get_keys()
-        );
+        let snapshot = Snapshot::poll(&mut self.source);
 
-        self.pressed_keys.len() == self.key_binds.len()
-            && previous_pressed_keys != self.pressed_keys
-            && self.pressed_keys == self.key_binds
+        self.binding.update(&snapshot.inputs, Instant::now()).triggered
     }
 
-    /// Sets provided callback that will be executed on trigger.
+    /// Returns the id of the keybind, as reported by [`TriggerEvent::binding`].
+    pub fn id(&self) -> BindingId {
+        self.binding.id()
+    }
+
+    /// Returns the keys of the keybind, e.g. to show them to the user.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    ///
+    /// let keybind = Keybind::new(&[Keycode::LControl, Keycode::G]);
+    ///
+    /// println!("Press {} to continue", keybind.keys());
+    /// ```
+    pub fn keys(&self) -> &KeySequence {
+        self.binding.sequence()
+    }
+
+    /// Sets provided callback that will be executed on trigger, replacing the one set before.
+    ///
+    /// Use [`add_listener`](Keybind::add_listener) to run several independent callbacks.
     ///
     /// # Example
     ///
@@ -85,16 +286,207 @@ impl Keybind {
     /// use keybind::{Keybind, Keycode};
     ///
     /// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::G]);
+    /// let mut count = 0;
     ///
-    /// keybind.on_trigger(|| {
-    ///     println!("This will be printed when you press CTRL+G");
+    /// keybind.on_trigger(move || {
+    ///     count += 1;
+    ///     println!("CTRL+G has been pressed {} times", count);
+    /// });
+    /// ```
+    pub fn on_trigger<C, R>(&mut self, mut callback: C)
+    where
+        C: FnMut() -> R + 'static,
+        R: CallbackResult,
+    {
+        self.on_trigger = callback::event_callback(move |_| callback());
+    }
+
+    /// Same as [`on_trigger`](Keybind::on_trigger), but provided callback receives a [`TriggerEvent`] describing
+    /// the trigger.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::G]);
+    ///
+    /// keybind.on_trigger_event(|event| {
+    ///     println!("{} pressed with {:?} held", event.combo(), event.keys());
+    /// });
+    /// ```
+    pub fn on_trigger_event<C, R>(&mut self, callback: C)
+    where
+        C: FnMut(&TriggerEvent) -> R + 'static,
+        R: CallbackResult,
+    {
+        self.on_trigger = callback::event_callback(callback);
+    }
+
+    /// Sets provided async handler, whose future gets handed to the spawner of the async runtime on every trigger,
+    /// right after the callback set with [`on_trigger`](Keybind::on_trigger). The [`Concurrency`] decides what
+    /// happens when the keybind triggers again before the handler finished.
+    ///
+    /// Unlike the other callbacks it is carried over to the background thread of [`spawn`](Keybind::spawn),
+    /// [`events`](Keybind::events) and [`stream`](Keybind::stream), so the blocking polling stays off the runtime.
+    ///
+    /// Requires the `async` feature.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Concurrency, Keybind, Keycode};
+    /// use tokio::runtime::Handle;
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::G]);
+    /// let runtime = Handle::current();
+    ///
+    /// keybind.on_trigger_async(Concurrency::Drop, move |future| { runtime.spawn(future); }, |event| async move {
+    ///     let response = reqwest::get("https://example.com").await;
+    ///     println!("{} fetched {:?}", event.combo(), response);
     /// });
+    /// let events = keybind.events();
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn on_trigger_async<Sp, C, F>(&mut self, concurrency: Concurrency, spawn: Sp, handler: C)
+    where
+        Sp: FnMut(HandlerFuture) + Send + 'static,
+        C: FnMut(TriggerEvent) -> F + Send + 'static,
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let mut callback = task::async_callback(concurrency, spawn, handler);
+
+        self.on_trigger_async = Some(Box::new(move |event| {
+            callback(event);
+            Ok(Flow::Continue)
+        }));
+    }
+
+    /// Adds provided callback to the ones executed on trigger, after the one set with
+    /// [`on_trigger`](Keybind::on_trigger) and in the order they were added. A callback returning
+    /// [`Flow::Consumed`] skips the ones after it.
+    ///
+    /// Returns the id to [`remove_listener`](Keybind::remove_listener) the callback with.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::G]);
+    ///
+    /// let overlay = keybind.add_listener(|_| println!("Showing overlay"));
+    /// keybind.add_listener(|event| println!("Logging {}", event.combo()));
+    ///
+    /// keybind.remove_listener(overlay);
+    /// ```
+    pub fn add_listener<C, R>(&mut self, callback: C) -> SubscriptionId
+    where
+        C: FnMut(&TriggerEvent) -> R + 'static,
+        R: CallbackResult,
+    {
+        let id = SubscriptionId::next();
+        self.listeners.push((id, callback::event_callback(callback)));
+
+        id
+    }
+
+    /// Removes a callback added with [`add_listener`](Keybind::add_listener), returns bool if it was added.
+    pub fn remove_listener(&mut self, id: SubscriptionId) -> bool {
+        let count = self.listeners.len();
+        self.listeners.retain(|(listener, _)| *listener != id);
+
+        self.listeners.len() != count
+    }
+
+    /// Sets provided callback that will be executed by [`wait`](Keybind::wait) when a callback returns an error or
+    /// panics, instead of printing the error to stderr.
+    ///
+    /// The loop keeps polling either way, so one failing callback does not stop the others.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    /// use std::fs;
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::S]);
+    ///
+    /// keybind.on_trigger(|| fs::write("notes.txt", "Saved by CTRL+S"));
+    /// keybind.on_error(|error| eprintln!("Could not save: {}", error));
+    /// ```
+    pub fn on_error<C: FnMut(CallbackError) + 'static>(&mut self, callback: C) {
+        self.on_error = Box::new(callback);
+    }
+
+    /// Sets provided callback that will be executed on the background thread started by [`spawn`](Keybind::spawn)
+    /// when its callback returns an error or panics, instead of printing the error to stderr.
+    ///
+    /// Unlike [`on_error`](Keybind::on_error) it has to be `Send`, to be moved to that thread.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    /// use std::fs;
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::S]);
+    ///
+    /// keybind.on_listener_error(|error| eprintln!("Could not save: {}", error));
+    /// let listener = keybind.spawn(|| fs::write("notes.txt", "Saved by CTRL+S"));
     /// ```
-    pub fn on_trigger<C: Fn() + 'static>(&mut self, callback: C) {
-        self.on_trigger = Box::new(callback);
+    pub fn on_listener_error<C: FnMut(CallbackError) + Send + 'static>(&mut self, callback: C) {
+        self.on_listener_error = Box::new(callback);
     }
 
-    /// Starts an infinite loop and calls provided callback when the keybind is triggered.
+    /// Sets provided callback that will be executed by [`wait`](Keybind::wait) when the keys get pressed, regardless
+    /// of the [`Phase`].
+    ///
+    /// Together with [`on_held`](Keybind::on_held) and [`on_release`](Keybind::on_release) this follows the keys
+    /// for as long as they are held, e.g. for push-to-talk.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::Space]);
+    ///
+    /// keybind.on_press(|| println!("Recording"));
+    /// keybind.on_held(|held| println!("Recording for {:?}", held));
+    /// keybind.on_release(|| println!("Stopped recording"));
+    /// ```
+    pub fn on_press<C, R>(&mut self, mut callback: C)
+    where
+        C: FnMut() -> R + 'static,
+        R: CallbackResult,
+    {
+        self.on_press = Some(Box::new(move || callback().into_result()));
+    }
+
+    /// Sets provided callback that will be executed by [`wait`](Keybind::wait) on every poll while the keys stay
+    /// pressed, with how long they have been held.
+    ///
+    /// Polls happen at the active [`PollInterval`] while keys are held, which is how often the callback ticks.
+    pub fn on_held<C, R>(&mut self, mut callback: C)
+    where
+        C: FnMut(Duration) -> R + 'static,
+        R: CallbackResult,
+    {
+        self.on_held = Some(Box::new(move |held| callback(held).into_result()));
+    }
+
+    /// Sets provided callback that will be executed by [`wait`](Keybind::wait) once pressed keys stop matching.
+    pub fn on_release<C, R>(&mut self, mut callback: C)
+    where
+        C: FnMut() -> R + 'static,
+        R: CallbackResult,
+    {
+        self.on_release = Some(Box::new(move || callback().into_result()));
+    }
+
+    /// Starts a loop and calls provided callback when the keybind is triggered, until stopped with the
+    /// [`StopHandle`] returned by [`stop_handle`](Keybind::stop_handle) or by a callback returning [`Flow::Stop`].
     ///
     /// # Example
     ///
@@ -112,10 +504,803 @@ impl Keybind {
     ///}
     /// ```
     pub fn wait(&mut self) {
-        loop {
-            if self.triggered() {
-                (self.on_trigger)();
+        let stop = self.stop.clone();
+
+        poll::run(&stop, self.poll_interval, None, || self.poll());
+    }
+
+    /// Same as [`wait`](Keybind::wait), but returns after the timeout at the latest.
+    ///
+    /// Returns bool if the loop was stopped through the [`StopHandle`] rather than timing out.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    /// use std::time::Duration;
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::G]);
+    ///
+    /// if !keybind.wait_timeout(Duration::from_secs(5)) {
+    ///     println!("Nothing happened in 5 seconds");
+    /// }
+    /// ```
+    pub fn wait_timeout(&mut self, timeout: Duration) -> bool {
+        let stop = self.stop.clone();
+
+        poll::run(&stop, self.poll_interval, Some(timeout), || self.poll())
+    }
+
+    /// Returns a handle that makes [`wait`](Keybind::wait) return, from a callback or another thread.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::Escape]);
+    /// let stop = keybind.stop_handle();
+    ///
+    /// keybind.on_trigger(move || stop.stop());
+    /// keybind.wait();
+    /// ```
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop.clone()
+    }
+
+    /// Sets when the keybind triggers relative to its keys being pressed and released, defaults to [`Phase::Press`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode, Phase};
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::LAlt]);
+    ///
+    /// keybind.set_phase(Phase::Tap);
+    /// keybind.on_trigger(|| {
+    ///     println!("This will be printed when you tap ALT on its own");
+    /// });
+    /// ```
+    pub fn set_phase(&mut self, phase: Phase) {
+        self.binding.set_phase(phase);
+    }
+
+    /// Sets how strictly the pressed keys have to match the keybind, defaults to [`MatchMode::Exact`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode, MatchMode};
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::LShift, Keycode::Space]);
+    ///
+    /// keybind.set_match_mode(MatchMode::Superset);
+    /// ```
+    pub fn set_match_mode(&mut self, mode: MatchMode) {
+        self.binding.set_match_mode(mode);
+    }
+
+    /// Sets how long [`wait`](Keybind::wait) sleeps between polls, see [`PollInterval`] for the trade-offs.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode};
+    /// use std::time::Duration;
+    ///
+    /// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::G]);
+    ///
+    /// keybind.set_poll_interval(Duration::from_millis(20));
+    /// ```
+    pub fn set_poll_interval<I: Into<PollInterval>>(&mut self, interval: I) {
+        self.poll_interval = interval.into();
+    }
+
+    /// Same as [`spawn`](Keybind::spawn), but the background thread reads pressed keys from the source created by
+    /// provided function.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{Keybind, Keycode, MockKeySource};
+    ///
+    /// let keybind = Keybind::with_source(&[Keycode::LControl, Keycode::G], MockKeySource::new());
+    /// let listener = keybind.spawn_with(|| MockKeySource::new().press(&[Keycode::LControl, Keycode::G]), || {
+    ///     println!("triggered");
+    /// });
+    /// ```
+    pub fn spawn_with<T, F, C, R>(self, source: F, mut callback: C) -> ListenerHandle
+    where
+        T: KeySource,
+        F: FnOnce() -> T + Send + 'static,
+        C: FnMut() -> R + Send + 'static,
+        R: CallbackResult,
+    {
+        self.listen(source, move |_| callback(), || false)
+    }
+
+    /// Same as [`events`](Keybind::events), but the background thread reads pressed keys from the source created
+    /// by provided function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use keybind::{Keybind, Keycode, MockKeySource};
+    ///
+    /// let keybind = Keybind::with_source(&[Keycode::LControl, Keycode::G], MockKeySource::new());
+    /// let stop = keybind.stop_handle();
+    /// let events = keybind.events_with(|| MockKeySource::new().press(&[Keycode::LControl, Keycode::G]));
+    ///
+    /// assert_eq!(events.recv().unwrap().keys(), &[Keycode::LControl, Keycode::G]);
+    /// stop.stop();
+    /// ```
+    pub fn events_with<T, F>(self, source: F) -> Receiver<TriggerEvent>
+    where
+        T: KeySource,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let stop = self.stop.clone();
+
+        self.listen(
+            source,
+            move |event| {
+                if sender.send(event.clone()).is_err() {
+                    stop.stop();
+                }
+            },
+            || false,
+        )
+        .detach();
+
+        receiver
+    }
+
+    /// Same as [`stream`](Keybind::stream), but the background thread reads pressed keys from the source created
+    /// by provided function.
+    ///
+    /// Requires the `async` feature.
+    #[cfg(feature = "async")]
+    pub fn stream_with<T, F>(self, source: F) -> TriggerStream
+    where
+        T: KeySource,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (sender, receiver) = futures_channel::mpsc::unbounded::<TriggerEvent>();
+        let closed = sender.clone();
+
+        self.listen(
+            source,
+            move |event| {
+                let _ = sender.unbounded_send(event.clone());
+            },
+            move || closed.is_closed(),
+        )
+        .detach();
+
+        TriggerStream::new(receiver)
+    }
+
+    /// Moves the binding to a background thread polling the source created by provided function, until stopped or
+    /// `closed` returns true.
+    fn listen<T, F, C, R, K>(self, source: F, on_trigger: C, closed: K) -> ListenerHandle
+    where
+        T: KeySource,
+        F: FnOnce() -> T + Send + 'static,
+        C: FnMut(&TriggerEvent) -> R + Send + 'static,
+        R: CallbackResult,
+        K: Fn() -> bool + Send + 'static,
+    {
+        let Keybind { binding, on_trigger_async, on_listener_error, poll_interval, stop, .. } = self;
+
+        ListenerHandle::spawn(stop.clone(), move || {
+            let mut keybind = Keybind {
+                source: source(),
+                binding,
+                on_trigger: callback::event_callback(on_trigger),
+                on_trigger_async,
+                listeners: Vec::new(),
+                on_press: None,
+                on_held: None,
+                on_release: None,
+                on_error: on_listener_error,
+                on_listener_error: Box::new(callback::print_error),
+                poll_interval,
+                stop: stop.clone(),
+            };
+
+            poll::run(&stop, poll_interval, None, || {
+                if closed() {
+                    stop.stop();
+                    return false;
+                }
+
+                keybind.poll()
+            });
+        })
+    }
+
+    /// Polls once and calls the callback if triggered, returns bool if any key was held.
+    fn poll(&mut self) -> bool {
+        let snapshot = Snapshot::poll(&mut self.source);
+        let now = Instant::now();
+        let update = self.binding.update(&snapshot.inputs, now);
+        let id = self.binding.id();
+
+        let lifecycle = match (update.state, &mut self.on_press, &mut self.on_held, &mut self.on_release) {
+            (Some(KeyState::Pressed), Some(on_press), _, _) => callback::invoke(id, on_press),
+            (Some(KeyState::Held(duration)), _, Some(on_held), _) => callback::invoke(id, || on_held(duration)),
+            (Some(KeyState::Released), _, _, Some(on_release)) => callback::invoke(id, on_release),
+            _ => Ok(Flow::Continue),
+        };
+
+        match lifecycle {
+            Ok(Flow::Stop) => self.stop.stop(),
+            Ok(Flow::Continue) | Ok(Flow::Consumed) => {}
+            Err(error) => (self.on_error)(error),
+        }
+
+        if update.triggered {
+            let event = TriggerEvent::new(&self.binding, &snapshot, now, update.repeat);
+            let on_trigger_async = self.on_trigger_async.iter_mut().map(|handler| -> &mut EventFn { &mut **handler });
+            let listeners = self.listeners.iter_mut().map(|(_, listener)| -> &mut EventFn { &mut **listener });
+
+            for listener in iter::once(&mut *self.on_trigger).chain(on_trigger_async).chain(listeners) {
+                match callback::invoke(id, || listener(&event)) {
+                    Ok(Flow::Continue) => {}
+                    Ok(Flow::Stop) => self.stop.stop(),
+                    Ok(Flow::Consumed) => break,
+                    Err(error) => (self.on_error)(error),
+                }
             }
         }
+
+        !snapshot.inputs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use std::sync::mpsc;
+
+    fn ctrl_g(source: MockKeySource) -> Keybind<MockKeySource> {
+        Keybind::with_source(&[Keycode::LControl, Keycode::G], source)
+    }
+
+    #[test]
+    fn accepts_keys_collected_at_runtime() {
+        let keys: Vec<Keycode> = vec![Keycode::LControl, Keycode::G];
+        let mut keybind = Keybind::with_source(&keys, MockKeySource::new().press(&keys));
+
+        assert!(keybind.triggered());
+    }
+
+    #[test]
+    fn triggers_when_keybind_is_pressed() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl]).press(&[Keycode::G]));
+
+        assert!(!keybind.triggered());
+        assert!(keybind.triggered());
+    }
+
+    #[test]
+    fn triggers_once_while_keybind_is_held() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::G]).hold(3));
+
+        assert!(keybind.triggered());
+        assert!(!keybind.triggered());
+        assert!(!keybind.triggered());
+        assert!(!keybind.triggered());
+    }
+
+    #[test]
+    fn triggers_again_after_release() {
+        let mut keybind = ctrl_g(
+            MockKeySource::new()
+                .press(&[Keycode::LControl, Keycode::G])
+                .release(&[Keycode::G])
+                .press(&[Keycode::G]),
+        );
+
+        assert!(keybind.triggered());
+        assert!(!keybind.triggered());
+        assert!(keybind.triggered());
+    }
+
+    #[test]
+    fn triggers_regardless_of_reported_key_order() {
+        let mut keybind = ctrl_g(MockKeySource::new().frame(&[Keycode::G, Keycode::LControl]));
+
+        assert!(keybind.triggered());
+    }
+
+    #[test]
+    fn triggers_regardless_of_keybind_order() {
+        let source = MockKeySource::new().frame(&[Keycode::LControl, Keycode::G]);
+        let mut keybind = Keybind::with_source(&[Keycode::G, Keycode::LControl], source);
+
+        assert!(keybind.triggered());
+    }
+
+    #[test]
+    fn does_not_trigger_when_only_reported_order_changes() {
+        let mut keybind = ctrl_g(
+            MockKeySource::new()
+                .frame(&[Keycode::LControl, Keycode::G])
+                .frame(&[Keycode::G, Keycode::LControl]),
+        );
+
+        assert!(keybind.triggered());
+        assert!(!keybind.triggered());
+    }
+
+    #[test]
+    fn modifier_triggers_with_either_side() {
+        let keys = [KeyMatcher::from(Modifier::Control), Keycode::G.into()];
+        let mut left = Keybind::with_source(&keys, MockKeySource::new().frame(&[Keycode::LControl, Keycode::G]));
+        let mut right = Keybind::with_source(&keys, MockKeySource::new().frame(&[Keycode::RControl, Keycode::G]));
+
+        assert!(left.triggered());
+        assert!(right.triggered());
+    }
+
+    #[test]
+    fn sided_key_ignores_other_side() {
+        let mut keybind = ctrl_g(MockKeySource::new().frame(&[Keycode::RControl, Keycode::G]));
+
+        assert!(!keybind.triggered());
+    }
+
+    #[test]
+    fn sequence_triggers_once_every_step_is_pressed() {
+        let source = MockKeySource::new()
+            .press(&[Keycode::LControl, Keycode::X])
+            .release(&[Keycode::X])
+            .press(&[Keycode::S]);
+        let mut keybind = Keybind::with_source("Ctrl+X Ctrl+S".parse::<KeySequence>().unwrap(), source);
+
+        assert!(!keybind.triggered());
+        assert!(!keybind.triggered());
+        assert!(keybind.triggered());
+    }
+
+    #[test]
+    fn mouse_combo_triggers_on_click() {
+        let source = MockKeySource::new()
+            .press(&[Keycode::LControl])
+            .press_buttons(&[MouseButton::Middle])
+            .release_buttons(&[MouseButton::Middle]);
+        let mut keybind = Keybind::with_source("Ctrl+MiddleClick".parse::<KeySequence>().unwrap(), source);
+
+        assert!(!keybind.triggered());
+        assert!(keybind.triggered());
+        assert!(!keybind.triggered());
+    }
+
+    #[test]
+    fn clicking_does_not_interrupt_keyboard_combo() {
+        let mut keybind = ctrl_g(
+            MockKeySource::new()
+                .press_buttons(&[MouseButton::Left])
+                .press(&[Keycode::LControl, Keycode::G]),
+        );
+
+        assert!(!keybind.triggered());
+        assert!(keybind.triggered());
+    }
+
+    #[test]
+    fn release_phase_triggers_when_keybind_is_released() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::G]).release(&[Keycode::G]));
+
+        keybind.set_phase(Phase::Release);
+
+        assert!(!keybind.triggered());
+        assert!(keybind.triggered());
+    }
+
+    #[test]
+    fn does_not_trigger_with_extra_keys_pressed() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::G, Keycode::H]));
+
+        assert!(!keybind.triggered());
+    }
+
+    #[test]
+    fn does_not_trigger_on_partial_keybind() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::G]));
+
+        assert!(!keybind.triggered());
+        assert!(!keybind.triggered());
+    }
+
+    #[test]
+    fn does_not_trigger_for_other_keys() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::H]));
+
+        assert!(!keybind.triggered());
+    }
+
+    #[test]
+    fn triggers_when_extra_key_is_released() {
+        let mut keybind = ctrl_g(
+            MockKeySource::new()
+                .press(&[Keycode::LControl, Keycode::G, Keycode::H])
+                .release(&[Keycode::H]),
+        );
+
+        assert!(!keybind.triggered());
+        assert!(keybind.triggered());
+    }
+
+    #[test]
+    fn wait_calls_callback_until_stopped() {
+        let source = MockKeySource::new()
+            .press(&[Keycode::LControl, Keycode::G])
+            .release(&[Keycode::G])
+            .press(&[Keycode::G]);
+        let mut keybind = ctrl_g(source);
+        let count = Rc::new(Cell::new(0));
+        let stop = keybind.stop_handle();
+        let handle = count.clone();
+
+        keybind.on_trigger(move || {
+            handle.set(handle.get() + 1);
+
+            if handle.get() == 2 {
+                stop.stop();
+            }
+        });
+        keybind.wait();
+
+        assert_eq!(count.get(), 2);
+        assert!(!keybind.stop_handle().is_stopped());
+    }
+
+    #[test]
+    fn wait_returns_right_away_when_stopped_beforehand() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::G]));
+        let count = Rc::new(Cell::new(0));
+        let handle = count.clone();
+
+        keybind.on_trigger(move || handle.set(handle.get() + 1));
+        keybind.stop_handle().stop();
+        keybind.wait();
+
+        assert_eq!(count.get(), 0);
+    }
+
+    #[test]
+    fn wait_timeout_returns_when_timed_out() {
+        let mut keybind = ctrl_g(MockKeySource::new());
+
+        assert!(!keybind.wait_timeout(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn wait_timeout_returns_when_stopped() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::G]));
+        let stop = keybind.stop_handle();
+
+        keybind.on_trigger(move || stop.stop());
+
+        assert!(keybind.wait_timeout(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn wait_timeout_accepts_endless_timeout() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::G]));
+        let stop = keybind.stop_handle();
+
+        keybind.on_trigger(move || stop.stop());
+
+        assert!(keybind.wait_timeout(Duration::MAX));
+    }
+
+    #[test]
+    fn wait_follows_lifecycle_of_keys() {
+        let source = MockKeySource::new()
+            .press(&[Keycode::LControl])
+            .press(&[Keycode::G])
+            .hold(2)
+            .release(&[Keycode::G])
+            .hold(1);
+        let mut keybind = ctrl_g(source);
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let stop = keybind.stop_handle();
+
+        keybind.set_poll_interval(Duration::ZERO);
+        let press_events = events.clone();
+        keybind.on_press(move || press_events.borrow_mut().push("press"));
+        let held_events = events.clone();
+        keybind.on_held(move |_| held_events.borrow_mut().push("held"));
+        let release_events = events.clone();
+        keybind.on_release(move || {
+            release_events.borrow_mut().push("release");
+            stop.stop();
+        });
+        let trigger_events = events.clone();
+        keybind.on_trigger(move || trigger_events.borrow_mut().push("trigger"));
+        keybind.wait();
+
+        assert_eq!(*events.borrow(), vec!["press", "trigger", "held", "held", "release"]);
+    }
+
+    #[test]
+    fn wait_passes_trigger_event_to_callback() {
+        let source = MockKeySource::new()
+            .move_mouse(40, 2)
+            .press(&[Keycode::LShift])
+            .press(&[Keycode::LControl, Keycode::G]);
+        let mut keybind = ctrl_g(source);
+        let stop = keybind.stop_handle();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let handle = events.clone();
+
+        keybind.set_match_mode(MatchMode::Superset);
+        keybind.on_trigger_event(move |event| {
+            handle.borrow_mut().push(event.clone());
+            stop.stop();
+        });
+        keybind.wait();
+
+        let events = events.borrow();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].binding(), keybind.id());
+        assert_eq!(*events[0].combo(), KeyCombo::new(&[Keycode::LControl, Keycode::G]));
+        assert_eq!(events[0].keys(), &[Keycode::LShift, Keycode::LControl, Keycode::G]);
+        assert_eq!(events[0].repeat(), 0);
+        assert_eq!(events[0].mouse_position(), (40, 2));
+    }
+
+    #[test]
+    fn wait_calls_callback_owning_its_state() {
+        let source = MockKeySource::new()
+            .press(&[Keycode::LControl, Keycode::G])
+            .release(&[Keycode::G])
+            .press(&[Keycode::G]);
+        let mut keybind = ctrl_g(source);
+        let stop = keybind.stop_handle();
+        let mut count = 0;
+
+        keybind.on_trigger(move || {
+            count += 1;
+
+            if count == 2 {
+                stop.stop();
+            }
+        });
+
+        assert!(keybind.wait_timeout(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn wait_reports_failing_callbacks_and_keeps_polling() {
+        let source = MockKeySource::new()
+            .press(&[Keycode::LControl, Keycode::G])
+            .release(&[Keycode::G])
+            .press(&[Keycode::G])
+            .release(&[Keycode::G])
+            .press(&[Keycode::G]);
+        let mut keybind = ctrl_g(source);
+        let stop = keybind.stop_handle();
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let handle = errors.clone();
+        let mut count = 0;
+
+        keybind.on_trigger(move || {
+            count += 1;
+
+            match count {
+                1 => Err("first".to_string()),
+                2 => panic!("second"),
+                _ => {
+                    stop.stop();
+                    Ok(())
+                }
+            }
+        });
+        keybind.on_error(move |error| handle.borrow_mut().push((error.kind(), error.message().to_string())));
+
+        assert!(keybind.wait_timeout(Duration::from_secs(60)));
+        assert_eq!(*errors.borrow(), vec![
+            (CallbackErrorKind::Failed, "first".to_string()),
+            (CallbackErrorKind::Panicked, "second".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn wait_returns_when_callback_returns_stop() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::G]));
+
+        keybind.on_trigger(|| Flow::Stop);
+
+        assert!(keybind.wait_timeout(Duration::from_secs(60)));
+        assert!(!keybind.stop_handle().is_stopped());
+    }
+
+    #[test]
+    fn consumed_trigger_skips_later_listeners() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::G]));
+        let count = Rc::new(Cell::new(0));
+        let handle = count.clone();
+
+        keybind.on_trigger(|| Ok::<_, String>(Flow::Consumed));
+        keybind.add_listener(move |_| handle.set(handle.get() + 1));
+        keybind.on_release(|| Flow::Stop);
+        keybind.on_held(|_| Flow::Stop);
+
+        assert!(keybind.wait_timeout(Duration::from_secs(60)));
+        assert_eq!(count.get(), 0);
+    }
+
+    #[test]
+    fn wait_calls_every_listener() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::G]));
+        let stop = keybind.stop_handle();
+        let calls = Rc::new(RefCell::new(Vec::new()));
+
+        let handle = calls.clone();
+        keybind.on_trigger(move || handle.borrow_mut().push("trigger"));
+        let handle = calls.clone();
+        keybind.add_listener(move |_| handle.borrow_mut().push("first"));
+        let handle = calls.clone();
+        keybind.add_listener(move |_| handle.borrow_mut().push("second"));
+        keybind.add_listener(move |_| stop.stop());
+        keybind.wait();
+
+        assert_eq!(*calls.borrow(), vec!["trigger", "first", "second"]);
+    }
+
+    #[test]
+    fn removed_listener_is_not_called() {
+        let mut keybind = ctrl_g(MockKeySource::new().press(&[Keycode::LControl, Keycode::G]));
+        let stop = keybind.stop_handle();
+        let count = Rc::new(Cell::new(0));
+        let handle = count.clone();
+
+        let id = keybind.add_listener(move |_| handle.set(handle.get() + 1));
+        keybind.add_listener(move |_| stop.stop());
+
+        assert!(keybind.remove_listener(id));
+        assert!(!keybind.remove_listener(id));
+        keybind.wait();
+        assert_eq!(count.get(), 0);
+    }
+
+    #[test]
+    fn spawn_calls_callback_on_background_thread() {
+        let (sender, receiver) = mpsc::channel();
+        let keybind = ctrl_g(MockKeySource::new());
+        let listener = keybind.spawn_with(
+            || MockKeySource::new().press(&[Keycode::LControl, Keycode::G]),
+            move || sender.send(()).unwrap(),
+        );
+
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        listener.stop();
+        listener.join().unwrap();
+    }
+
+    #[test]
+    fn spawned_callback_owns_its_state() {
+        let (sender, receiver) = mpsc::channel();
+        let source = || {
+            MockKeySource::new()
+                .press(&[Keycode::LControl, Keycode::G])
+                .release(&[Keycode::G])
+                .press(&[Keycode::G])
+        };
+        let mut count = 0;
+        let listener = ctrl_g(MockKeySource::new()).spawn_with(source, move || {
+            count += 1;
+            sender.send(count).unwrap();
+        });
+
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(1));
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(2));
+        listener.stop();
+        listener.join().unwrap();
+    }
+
+    #[test]
+    fn events_receives_every_trigger() {
+        let source = || {
+            MockKeySource::new()
+                .press(&[Keycode::LControl, Keycode::G])
+                .release(&[Keycode::G])
+                .press(&[Keycode::G])
+        };
+        let keybind = ctrl_g(MockKeySource::new());
+        let id = keybind.id();
+        let stop = keybind.stop_handle();
+        let events = keybind.events_with(source);
+
+        for _ in 0..2 {
+            let event = events.recv_timeout(Duration::from_secs(5)).unwrap();
+
+            assert_eq!(event.binding(), id);
+            assert_eq!(event.keys(), &[Keycode::LControl, Keycode::G]);
+        }
+        stop.stop();
+        assert_eq!(events.recv_timeout(Duration::from_secs(5)), Err(mpsc::RecvTimeoutError::Disconnected));
+    }
+
+    #[test]
+    fn spawned_listener_reports_failing_callback() {
+        let (sender, receiver) = mpsc::channel();
+        let mut keybind = ctrl_g(MockKeySource::new());
+        let stop = keybind.stop_handle();
+
+        keybind.on_listener_error(move |error| {
+            sender.send(error.message().to_string()).unwrap();
+            stop.stop();
+        });
+        let listener = keybind.spawn_with(
+            || MockKeySource::new().press(&[Keycode::LControl, Keycode::G]),
+            || Err::<(), _>("no terminal"),
+        );
+
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok("no terminal".to_string()));
+        listener.join().unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn spawned_listener_runs_async_handler() {
+        let (sender, receiver) = mpsc::channel();
+        let (spawner, spawned) = mpsc::channel();
+        let mut keybind = ctrl_g(MockKeySource::new());
+        let id = keybind.id();
+
+        keybind.on_trigger_async(Concurrency::Concurrent, move |future| spawner.send(future).unwrap(), move |event| {
+            sender.send(event.binding()).unwrap();
+            async {}
+        });
+        let listener = keybind.spawn_with(|| MockKeySource::new().press(&[Keycode::LControl, Keycode::G]), || {});
+
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(id));
+        assert!(spawned.recv_timeout(Duration::from_secs(5)).is_ok());
+        listener.stop();
+        listener.join().unwrap();
+    }
+
+    #[test]
+    fn spawned_listener_finishes_once_stopped() {
+        let keybind = ctrl_g(MockKeySource::new());
+        let listener = keybind.spawn_with(MockKeySource::new, || {});
+
+        listener.stop();
+
+        while !listener.is_finished() {
+            std::thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn dropping_spawned_listener_joins_thread() {
+        let (sender, receiver) = mpsc::channel::<()>();
+        let keybind = ctrl_g(MockKeySource::new());
+        let listener = keybind.spawn_with(MockKeySource::new, move || sender.send(()).unwrap());
+
+        drop(listener);
+
+        assert!(receiver.recv().is_err());
+    }
+
+    #[test]
+    fn spawned_listener_stops_from_callback() {
+        let keybind = ctrl_g(MockKeySource::new());
+        let stop = keybind.stop_handle();
+        let listener = keybind.spawn_with(
+            || MockKeySource::new().press(&[Keycode::LControl, Keycode::G]),
+            move || stop.stop(),
+        );
+
+        listener.join().unwrap();
     }
 }
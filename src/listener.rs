@@ -0,0 +1,60 @@
+use crate::StopHandle;
+use std::thread::{self, JoinHandle};
+
+/// Owns a keybind polling on a background thread, see [`Keybind::spawn`](crate::Keybind::spawn).
+///
+/// Dropping the handle stops the loop and waits for the thread to finish.
+#[derive(Debug)]
+pub struct ListenerHandle {
+    stop: StopHandle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ListenerHandle {
+    pub(crate) fn spawn<F: FnOnce() + Send + 'static>(stop: StopHandle, listen: F) -> ListenerHandle {
+        ListenerHandle {
+            stop,
+            thread: Some(thread::spawn(listen)),
+        }
+    }
+
+    /// Requests the background loop to return, without waiting for it.
+    pub fn stop(&self) {
+        self.stop.stop();
+    }
+
+    /// Returns a handle that stops the background loop, e.g. from inside its own callback.
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop.clone()
+    }
+
+    /// Returns bool if the background loop has returned.
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().map_or(true, JoinHandle::is_finished)
+    }
+
+    /// Lets the background loop run on without the handle, so dropping it does not stop the loop.
+    pub(crate) fn detach(mut self) {
+        self.thread.take();
+    }
+
+    /// Waits for the background loop to return, which it only does once stopped.
+    ///
+    /// Returns an error if the background thread panicked, which panicking callbacks do not cause as their panics are
+    /// passed to the hook set with [`on_listener_error`](crate::Keybind::on_listener_error).
+    pub fn join(mut self) -> thread::Result<()> {
+        match self.thread.take() {
+            Some(thread) => thread.join(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for ListenerHandle {
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.stop.stop();
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,436 @@
+use crate::binding::Binding;
+use crate::callback::{self, ErrorCallback, EventCallback};
+use crate::input::Snapshot;
+use crate::poll;
+use crate::{
+    BindingId, CallbackError, CallbackResult, Flow, KeySequence, KeySource, MatchMode, Phase, PollInterval, StopHandle,
+    TriggerEvent,
+};
+use device_query::DeviceState;
+use std::time::{Duration, Instant};
+
+struct Entry {
+    binding: Binding,
+    callback: EventCallback,
+}
+
+/// Dispatches any number of keybinds from a single poll of the keyboard.
+///
+/// Every poll queries the [`KeySource`] once and calls the callback of each registered combo that got triggered,
+/// in registration order.
+///
+/// # Example
+///
+/// ```ignore
+/// use keybind::{KeybindManager, Keycode};
+///
+///fn main() {
+///    let mut manager = KeybindManager::new();
+///
+///    manager.register(&[Keycode::LControl, Keycode::G], || {
+///        println!("This will be printed when you press CTRL+G");
+///    });
+///    manager.register(&[Keycode::LControl, Keycode::H], || {
+///        println!("This will be printed when you press CTRL+H");
+///    });
+///
+///    manager.wait();
+///}
+/// ```
+pub struct KeybindManager<S = DeviceState> {
+    source: S,
+    entries: Vec<Entry>,
+    on_error: ErrorCallback,
+    poll_interval: PollInterval,
+    stop: StopHandle,
+}
+
+impl KeybindManager {
+    /// Constructs a new `KeybindManager` without any bindings.
+    pub fn new() -> KeybindManager {
+        KeybindManager::with_source(DeviceState::new())
+    }
+}
+
+impl Default for KeybindManager {
+    fn default() -> KeybindManager {
+        KeybindManager::new()
+    }
+}
+
+impl<S: KeySource> KeybindManager<S> {
+    /// Constructs a new `KeybindManager` that reads pressed keys from the provided source instead of the OS.
+    pub fn with_source(source: S) -> KeybindManager<S> {
+        KeybindManager {
+            source,
+            entries: Vec::new(),
+            on_error: Box::new(callback::print_error),
+            poll_interval: PollInterval::default(),
+            stop: StopHandle::default(),
+        }
+    }
+
+    /// Registers a callback that will be executed when the provided combo or [`KeySequence`] is triggered.
+    ///
+    /// Bindings registered first take precedence: a callback returning [`Flow::Consumed`] keeps the bindings
+    /// registered after it from being called for the same poll.
+    ///
+    /// Returns the id to [`unregister`](KeybindManager::unregister) the binding with.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{KeybindManager, Keycode};
+    ///
+    /// let mut manager = KeybindManager::new();
+    ///
+    /// let id = manager.register(&[Keycode::LControl, Keycode::G], || {
+    ///     println!("This will be printed when you press CTRL+G");
+    /// });
+    /// ```
+    pub fn register<K, C, R>(&mut self, keys: K, mut callback: C) -> BindingId
+    where
+        K: Into<KeySequence>,
+        C: FnMut() -> R + 'static,
+        R: CallbackResult,
+    {
+        self.register_event(keys, move |_| callback())
+    }
+
+    /// Same as [`register`](KeybindManager::register), but provided callback receives a [`TriggerEvent`], e.g. to
+    /// share a single callback between bindings.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use keybind::{KeybindManager, Keycode};
+    ///
+    /// let mut manager = KeybindManager::new();
+    ///
+    /// for key in &[Keycode::Key1, Keycode::Key2, Keycode::Key3] {
+    ///     manager.register_event(&[Keycode::LAlt, key.clone()], |event| {
+    ///         println!("Switching to workspace {}", event.combo());
+    ///     });
+    /// }
+    /// ```
+    pub fn register_event<K, C, R>(&mut self, keys: K, callback: C) -> BindingId
+    where
+        K: Into<KeySequence>,
+        C: FnMut(&TriggerEvent) -> R + 'static,
+        R: CallbackResult,
+    {
+        let binding = Binding::new(keys.into());
+        let id = binding.id();
+
+        self.entries.push(Entry {
+            binding,
+            callback: callback::event_callback(callback),
+        });
+
+        id
+    }
+
+    /// Removes a registered binding, returns bool if it was registered.
+    pub fn unregister(&mut self, id: BindingId) -> bool {
+        let count = self.entries.len();
+        self.entries.retain(|entry| entry.binding.id() != id);
+
+        self.entries.len() != count
+    }
+
+    /// Sets when a registered binding triggers, returns bool if it was registered.
+    pub fn set_phase(&mut self, id: BindingId, phase: Phase) -> bool {
+        match self.entry_mut(id) {
+            Some(entry) => {
+                entry.binding.set_phase(phase);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets how strictly the pressed keys have to match a registered binding, returns bool if it was registered.
+    pub fn set_match_mode(&mut self, id: BindingId, mode: MatchMode) -> bool {
+        match self.entry_mut(id) {
+            Some(entry) => {
+                entry.binding.set_match_mode(mode);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets provided callback that will be executed when a registered callback returns an error or panics, instead
+    /// of printing the error to stderr.
+    ///
+    /// Dispatching goes on either way, so one failing callback does not prevent the others from running.
+    pub fn on_error<C: FnMut(CallbackError) + 'static>(&mut self, callback: C) {
+        self.on_error = Box::new(callback);
+    }
+
+    /// Returns the number of registered bindings.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns bool if no binding is registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sets how long [`wait`](KeybindManager::wait) sleeps between polls, see [`PollInterval`] for the trade-offs.
+    pub fn set_poll_interval<I: Into<PollInterval>>(&mut self, interval: I) {
+        self.poll_interval = interval.into();
+    }
+
+    /// Polls the keyboard once, calls the callbacks of all triggered bindings and returns their ids.
+    pub fn poll(&mut self) -> Vec<BindingId> {
+        let snapshot = Snapshot::poll(&mut self.source);
+
+        self.dispatch(&snapshot)
+    }
+
+    fn dispatch(&mut self, snapshot: &Snapshot) -> Vec<BindingId> {
+        let now = Instant::now();
+        let mut triggered = Vec::new();
+        let mut consumed = false;
+
+        for entry in &mut self.entries {
+            // Bindings after a consumed trigger still follow the keys, they just do not get called.
+            let update = entry.binding.update(&snapshot.inputs, now);
+
+            if !update.triggered || consumed {
+                continue;
+            }
+
+            let event = TriggerEvent::new(&entry.binding, snapshot, now, update.repeat);
+
+            match callback::invoke(entry.binding.id(), || (entry.callback)(&event)) {
+                Ok(Flow::Continue) => {}
+                Ok(Flow::Stop) => self.stop.stop(),
+                Ok(Flow::Consumed) => consumed = true,
+                Err(error) => (self.on_error)(error),
+            }
+            triggered.push(entry.binding.id());
+        }
+
+        triggered
+    }
+
+    /// Starts a loop polling the keyboard and dispatching to the registered callbacks, until stopped with the
+    /// [`StopHandle`] returned by [`stop_handle`](KeybindManager::stop_handle).
+    pub fn wait(&mut self) {
+        let stop = self.stop.clone();
+
+        poll::run(&stop, self.poll_interval, None, || self.poll_held());
+    }
+
+    /// Same as [`wait`](KeybindManager::wait), but returns after the timeout at the latest.
+    ///
+    /// Returns bool if the loop was stopped through the [`StopHandle`] rather than timing out.
+    pub fn wait_timeout(&mut self, timeout: Duration) -> bool {
+        let stop = self.stop.clone();
+
+        poll::run(&stop, self.poll_interval, Some(timeout), || self.poll_held())
+    }
+
+    /// Returns a handle that makes [`wait`](KeybindManager::wait) return, from a callback or another thread.
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop.clone()
+    }
+
+    fn entry_mut(&mut self, id: BindingId) -> Option<&mut Entry> {
+        self.entries.iter_mut().find(|entry| entry.binding.id() == id)
+    }
+
+    /// Polls once and dispatches, returns bool if any key was held.
+    fn poll_held(&mut self) -> bool {
+        let snapshot = Snapshot::poll(&mut self.source);
+        self.dispatch(&snapshot);
+
+        !snapshot.inputs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Keycode, MockKeySource};
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    fn counter() -> (Rc<Cell<usize>>, impl Fn()) {
+        let count = Rc::new(Cell::new(0));
+        let handle = count.clone();
+
+        (count, move || handle.set(handle.get() + 1))
+    }
+
+    #[test]
+    fn dispatches_to_matching_binding_only() {
+        let source = MockKeySource::new()
+            .press(&[Keycode::LControl, Keycode::G])
+            .release(&[Keycode::G])
+            .press(&[Keycode::H]);
+        let mut manager = KeybindManager::with_source(source);
+        let (g_count, g_callback) = counter();
+        let (h_count, h_callback) = counter();
+        let g = manager.register(&[Keycode::LControl, Keycode::G], g_callback);
+        let h = manager.register(&[Keycode::LControl, Keycode::H], h_callback);
+
+        assert_eq!(manager.poll(), vec![g]);
+        assert_eq!(manager.poll(), vec![]);
+        assert_eq!(manager.poll(), vec![h]);
+        assert_eq!(g_count.get(), 1);
+        assert_eq!(h_count.get(), 1);
+    }
+
+    #[test]
+    fn dispatches_to_every_binding_of_same_combo() {
+        let mut manager = KeybindManager::with_source(MockKeySource::new().press(&[Keycode::A]));
+        let (count, callback) = counter();
+        let first = manager.register(&[Keycode::A], callback);
+        let second = manager.register(&[Keycode::A], || {});
+
+        assert_eq!(manager.poll(), vec![first, second]);
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn shared_event_callback_tells_bindings_apart() {
+        let source = MockKeySource::new().press(&[Keycode::Key1]).release(&[Keycode::Key1]).press(&[Keycode::Key2]);
+        let mut manager = KeybindManager::with_source(source);
+        let triggered = Rc::new(RefCell::new(Vec::new()));
+        let mut ids = Vec::new();
+
+        for key in &[Keycode::Key1, Keycode::Key2] {
+            let handle = triggered.clone();
+            ids.push(manager.register_event(std::slice::from_ref(key), move |event| {
+                handle.borrow_mut().push(event.binding())
+            }));
+        }
+        manager.poll();
+        manager.poll();
+        manager.poll();
+
+        assert_eq!(*triggered.borrow(), ids);
+    }
+
+    #[test]
+    fn dispatches_to_callback_owning_its_state() {
+        let source = MockKeySource::new().press(&[Keycode::A]).release(&[Keycode::A]).press(&[Keycode::A]);
+        let mut manager = KeybindManager::with_source(source);
+        let mut presses = 0;
+        let (count, callback) = counter();
+
+        manager.register(&[Keycode::A], move || {
+            presses += 1;
+            if presses == 2 {
+                callback();
+            }
+        });
+        manager.poll();
+        manager.poll();
+        manager.poll();
+
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn failing_callback_does_not_prevent_others() {
+        let mut manager = KeybindManager::with_source(MockKeySource::new().press(&[Keycode::A]));
+        let (count, callback) = counter();
+        let failed = Rc::new(RefCell::new(Vec::new()));
+        let handle = failed.clone();
+        let failing = manager.register(&[Keycode::A], || Err::<(), _>("no terminal"));
+        let panicking = manager.register(&[Keycode::A], || -> Result<(), &str> { panic!("no cheese") });
+        manager.register(&[Keycode::A], callback);
+
+        manager.on_error(move |error| handle.borrow_mut().push(error.binding()));
+        manager.poll();
+
+        assert_eq!(*failed.borrow(), vec![failing, panicking]);
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn consumed_trigger_skips_later_bindings() {
+        let source = MockKeySource::new().press(&[Keycode::A]).release(&[Keycode::A]).press(&[Keycode::A]);
+        let mut manager = KeybindManager::with_source(source);
+        let (count, callback) = counter();
+        let mut presses = 0;
+        let first = manager.register(&[Keycode::A], move || {
+            presses += 1;
+            if presses == 1 {
+                Flow::Consumed
+            } else {
+                Flow::Continue
+            }
+        });
+        let second = manager.register(&[Keycode::A], callback);
+
+        assert_eq!(manager.poll(), vec![first]);
+        assert_eq!(manager.poll(), vec![]);
+        assert_eq!(manager.poll(), vec![first, second]);
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn wait_returns_when_callback_returns_stop() {
+        let source = MockKeySource::new().press(&[Keycode::A]).release(&[Keycode::A]).press(&[Keycode::Escape]);
+        let mut manager = KeybindManager::with_source(source);
+        let (count, callback) = counter();
+
+        manager.register(&[Keycode::A], callback);
+        manager.register(&[Keycode::Escape], || Flow::Stop);
+
+        assert!(manager.wait_timeout(Duration::from_secs(60)));
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn unregistered_binding_is_not_dispatched() {
+        let mut manager = KeybindManager::with_source(MockKeySource::new().press(&[Keycode::A]));
+        let (count, callback) = counter();
+        let id = manager.register(&[Keycode::A], callback);
+
+        assert!(manager.unregister(id));
+        assert!(!manager.unregister(id));
+        assert!(manager.is_empty());
+        assert!(manager.poll().is_empty());
+        assert_eq!(count.get(), 0);
+    }
+
+    #[test]
+    fn wait_returns_when_stopped_from_callback() {
+        let source = MockKeySource::new().press(&[Keycode::A]).release(&[Keycode::A]).press(&[Keycode::Escape]);
+        let mut manager = KeybindManager::with_source(source);
+        let (count, callback) = counter();
+        let stop = manager.stop_handle();
+
+        manager.register(&[Keycode::A], callback);
+        manager.register(&[Keycode::Escape], move || stop.stop());
+        manager.wait();
+
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn wait_timeout_returns_when_timed_out() {
+        let mut manager = KeybindManager::with_source(MockKeySource::new());
+
+        assert!(!manager.wait_timeout(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn dispatches_with_phase_of_each_binding() {
+        let source = MockKeySource::new().press(&[Keycode::LAlt]).release(&[Keycode::LAlt]);
+        let mut manager = KeybindManager::with_source(source);
+        let press = manager.register(&[Keycode::LAlt], || {});
+        let tap = manager.register(&[Keycode::LAlt], || {});
+
+        assert!(manager.set_phase(tap, Phase::Tap));
+        assert_eq!(manager.poll(), vec![press]);
+        assert_eq!(manager.poll(), vec![tap]);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{KeySource, MouseButton};
+use device_query::{Keycode, MouseState};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Default)]
+struct Frame {
+    keys: Vec<Keycode>,
+    buttons: Vec<MouseButton>,
+    position: (i32, i32),
+}
+
+/// A [`KeySource`] replaying a scripted timeline of frames, for testing bindings without a keyboard or display.
+///
+/// Every call to [`get_keys`](KeySource::get_keys) consumes one frame. Once the script is exhausted the last
+/// frame keeps being reported, as if the user stopped touching the keyboard. Mouse buttons and position scripted
+/// with [`press_buttons`](MockKeySource::press_buttons) or [`move_mouse`](MockKeySource::move_mouse) are reported
+/// by [`get_mouse`](KeySource::get_mouse) for the frame consumed last.
+///
+/// # Example
+///
+/// ```
+/// use keybind::{Keybind, Keycode, MockKeySource};
+///
+/// let source = MockKeySource::new()
+///     .press(&[Keycode::LControl])
+///     .press(&[Keycode::G])
+///     .release(&[Keycode::G, Keycode::LControl]);
+///
+/// let mut keybind = Keybind::with_source(&[Keycode::LControl, Keycode::G], source);
+///
+/// assert!(!keybind.triggered());
+/// assert!(keybind.triggered());
+/// assert!(!keybind.triggered());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockKeySource {
+    frames: VecDeque<Frame>,
+    scripted: Frame,
+    current: Frame,
+}
+
+impl MockKeySource {
+    /// Constructs a new `MockKeySource` with an empty script, reporting no pressed keys.
+    pub fn new() -> MockKeySource {
+        MockKeySource::default()
+    }
+
+    /// Appends a frame reporting exactly the provided keys as pressed, in the provided order.
+    ///
+    /// Mouse buttons held in the previous frame stay held.
+    pub fn frame(mut self, keys: &[Keycode]) -> MockKeySource {
+        self.scripted.keys = keys.to_vec();
+        self.frames.push_back(self.scripted.clone());
+        self
+    }
+
+    /// Appends a frame where the provided keys are pressed on top of the ones held in the previous frame.
+    pub fn press(self, keys: &[Keycode]) -> MockKeySource {
+        let mut held = self.scripted.keys.clone();
+        held.extend(keys.iter().filter(|key| !self.scripted.keys.contains(key)).cloned());
+
+        self.frame(&held)
+    }
+
+    /// Appends a frame where the provided keys are released from the ones held in the previous frame.
+    pub fn release(self, keys: &[Keycode]) -> MockKeySource {
+        let held: Vec<Keycode> = self.scripted.keys.iter().filter(|key| !keys.contains(key)).cloned().collect();
+
+        self.frame(&held)
+    }
+
+    /// Appends a frame where the provided mouse buttons are pressed on top of the ones held in the previous frame.
+    pub fn press_buttons(mut self, buttons: &[MouseButton]) -> MockKeySource {
+        for button in buttons {
+            if !self.scripted.buttons.contains(button) {
+                self.scripted.buttons.push(*button);
+            }
+        }
+        self.frames.push_back(self.scripted.clone());
+        self
+    }
+
+    /// Appends a frame where the provided mouse buttons are released from the ones held in the previous frame.
+    pub fn release_buttons(mut self, buttons: &[MouseButton]) -> MockKeySource {
+        self.scripted.buttons.retain(|button| !buttons.contains(button));
+        self.frames.push_back(self.scripted.clone());
+        self
+    }
+
+    /// Appends a frame where the mouse moved to the provided position, with the same keys and buttons pressed.
+    pub fn move_mouse(mut self, x: i32, y: i32) -> MockKeySource {
+        self.scripted.position = (x, y);
+        self.frames.push_back(self.scripted.clone());
+        self
+    }
+
+    /// Appends `frames` frames repeating the previous one, as if nothing changed between polls.
+    pub fn hold(mut self, frames: usize) -> MockKeySource {
+        for _ in 0..frames {
+            self.frames.push_back(self.scripted.clone());
+        }
+
+        self
+    }
+
+    /// Returns the number of scripted frames that have not been polled yet.
+    pub fn remaining(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` once every scripted frame has been polled.
+    pub fn is_exhausted(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+impl KeySource for MockKeySource {
+    fn get_keys(&mut self) -> Vec<Keycode> {
+        if let Some(frame) = self.frames.pop_front() {
+            self.current = frame;
+        }
+
+        self.current.keys.clone()
+    }
+
+    fn get_mouse(&mut self) -> MouseState {
+        let mut button_pressed = vec![false; 6];
+        for button in &self.current.buttons {
+            button_pressed[button.number()] = true;
+        }
+
+        MouseState {
+            coords: self.current.position,
+            button_pressed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_frames_in_order() {
+        let mut source = MockKeySource::new()
+            .frame(&[Keycode::A])
+            .frame(&[Keycode::B, Keycode::C])
+            .frame(&[]);
+
+        assert_eq!(source.get_keys(), vec![Keycode::A]);
+        assert_eq!(source.get_keys(), vec![Keycode::B, Keycode::C]);
+        assert_eq!(source.get_keys(), vec![]);
+        assert!(source.is_exhausted());
+    }
+
+    #[test]
+    fn press_and_release_build_on_previous_frame() {
+        let mut source = MockKeySource::new()
+            .press(&[Keycode::LShift])
+            .press(&[Keycode::A, Keycode::LShift])
+            .release(&[Keycode::LShift]);
+
+        assert_eq!(source.get_keys(), vec![Keycode::LShift]);
+        assert_eq!(source.get_keys(), vec![Keycode::LShift, Keycode::A]);
+        assert_eq!(source.get_keys(), vec![Keycode::A]);
+    }
+
+    #[test]
+    fn keeps_reporting_last_frame_once_exhausted() {
+        let mut source = MockKeySource::new().press(&[Keycode::Space]).hold(1);
+
+        assert_eq!(source.remaining(), 2);
+        assert_eq!(source.get_keys(), vec![Keycode::Space]);
+        assert_eq!(source.get_keys(), vec![Keycode::Space]);
+        assert_eq!(source.get_keys(), vec![Keycode::Space]);
+        assert_eq!(source.remaining(), 0);
+    }
+
+    #[test]
+    fn reports_buttons_of_current_frame() {
+        let mut source = MockKeySource::new()
+            .press_buttons(&[MouseButton::Left])
+            .press(&[Keycode::LControl])
+            .release_buttons(&[MouseButton::Left]);
+
+        assert_eq!(source.get_keys(), vec![]);
+        assert_eq!(MouseButton::pressed(&source.get_mouse()), vec![MouseButton::Left]);
+        assert_eq!(source.get_keys(), vec![Keycode::LControl]);
+        assert_eq!(MouseButton::pressed(&source.get_mouse()), vec![MouseButton::Left]);
+        assert_eq!(source.get_keys(), vec![Keycode::LControl]);
+        assert!(MouseButton::pressed(&source.get_mouse()).is_empty());
+    }
+
+    #[test]
+    fn reports_mouse_position_of_current_frame() {
+        let mut source = MockKeySource::new().move_mouse(10, 20).press(&[Keycode::A]);
+
+        source.get_keys();
+        assert_eq!(source.get_mouse().coords, (10, 20));
+        assert_eq!(source.get_keys(), vec![Keycode::A]);
+        assert_eq!(source.get_mouse().coords, (10, 20));
+    }
+
+    #[test]
+    fn reports_nothing_without_script() {
+        let mut source = MockKeySource::new();
+
+        assert!(source.get_keys().is_empty());
+    }
+}
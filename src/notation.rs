@@ -0,0 +1,406 @@
+use crate::parse::{self, ParseError, ParseErrorKind};
+use crate::{KeyCombo, KeyMatcher, Modifier, MouseButton};
+use device_query::Keycode;
+use std::fmt;
+
+/// The style a [`KeyCombo`] is written in.
+///
+/// | Notation | Example        |
+/// |----------|----------------|
+/// | `Plus`   | `Ctrl+Shift+G` |
+/// | `Emacs`  | `C-S-g`        |
+/// | `Vim`    | `<C-S-g>`      |
+/// | `Mac`    | `⌃⇧G`          |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Notation {
+    /// Key names joined by `+`, as used on Windows and Linux.
+    #[default]
+    Plus,
+    /// Emacs key description, with `C`, `M` and `S` prefixes for Ctrl, Alt and Shift.
+    Emacs,
+    /// Vim key notation, with `C`, `A` and `S` prefixes for Ctrl, Alt and Shift.
+    Vim,
+    /// macOS menu symbols, `⌃`, `⌥` and `⇧` for Ctrl, Alt and Shift.
+    Mac,
+}
+
+/// Helper to write a [`KeyCombo`] in a [`Notation`], returned by [`KeyCombo::display`].
+#[derive(Debug, Clone, Copy)]
+pub struct ComboDisplay<'a> {
+    combo: &'a KeyCombo,
+    notation: Notation,
+}
+
+impl KeyCombo {
+    /// Returns a value writing the combo in provided notation, which [`parse_notation`](KeyCombo::parse_notation)
+    /// reads back.
+    ///
+    /// Modifiers are written first, in Ctrl, Alt, Shift order, followed by the other keys. Without other keys, Emacs
+    /// and Vim notation write the last modifier as a named key, e.g. `C-<shift>` or `<C-Shift>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use keybind::{KeyCombo, Notation};
+    ///
+    /// let combo: KeyCombo = "Shift+G+Ctrl".parse().unwrap();
+    ///
+    /// assert_eq!(combo.to_string(), "Ctrl+Shift+G");
+    /// assert_eq!(combo.display(Notation::Emacs).to_string(), "C-S-g");
+    /// assert_eq!(combo.display(Notation::Vim).to_string(), "<C-S-g>");
+    /// assert_eq!(combo.display(Notation::Mac).to_string(), "⌃⇧G");
+    /// ```
+    pub fn display(&self, notation: Notation) -> ComboDisplay<'_> {
+        ComboDisplay { combo: self, notation }
+    }
+
+    /// Parses a combo written in provided notation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use keybind::{KeyCombo, Notation};
+    ///
+    /// let combo = KeyCombo::parse_notation("<C-S-g>", Notation::Vim).unwrap();
+    ///
+    /// assert_eq!(combo, "Ctrl+Shift+G".parse().unwrap());
+    /// ```
+    pub fn parse_notation(input: &str, notation: Notation) -> Result<KeyCombo, ParseError> {
+        match notation {
+            Notation::Plus => parse::parse_combo(input, 0),
+            Notation::Emacs => parse_prefixed(input, 0, |token| match token {
+                "C" => Some(Modifier::Control),
+                "M" | "A" => Some(Modifier::Alt),
+                "S" => Some(Modifier::Shift),
+                _ => None,
+            }),
+            Notation::Vim => {
+                let trimmed = input.trim();
+
+                match trimmed.strip_prefix('<').and_then(|inner| inner.strip_suffix('>')) {
+                    Some(inner) => parse_prefixed(inner, input.find('<').unwrap_or(0) + 1, vim_modifier),
+                    None => parse_prefixed(input, 0, |_| None),
+                }
+            }
+            Notation::Mac => parse_mac(input),
+        }
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display(Notation::Plus).fmt(f)
+    }
+}
+
+impl fmt::Display for ComboDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (modifiers, keys) = split(self.combo);
+        let last = modifiers.len().saturating_sub(1);
+        let lone = keys.is_empty();
+        let modifiers = modifiers.into_iter().enumerate().map(|(index, modifier)| match lone && index == last {
+            true => modifier_key_name(modifier, self.notation),
+            false => modifier_name(modifier, self.notation),
+        });
+        let keys = keys.into_iter().map(|key| matcher_name(key, self.notation));
+
+        match self.notation {
+            Notation::Plus => f.write_str(&modifiers.chain(keys).collect::<Vec<_>>().join("+")),
+            Notation::Emacs => f.write_str(&modifiers.chain(keys).collect::<Vec<_>>().join("-")),
+            Notation::Vim => {
+                let parts: Vec<String> = modifiers.chain(keys).collect();
+
+                if parts.len() == 1 && parts[0].chars().count() == 1 {
+                    f.write_str(&parts[0])
+                } else {
+                    write!(f, "<{}>", parts.join("-"))
+                }
+            }
+            Notation::Mac => {
+                let modifiers: String = modifiers.collect();
+
+                write!(f, "{}{}", modifiers, keys.collect::<Vec<_>>().join("+"))
+            }
+        }
+    }
+}
+
+/// Splits a combo into its side-agnostic modifiers and its keys and buttons, both in display order.
+fn split(combo: &KeyCombo) -> (Vec<Modifier>, Vec<&KeyMatcher>) {
+    let modifiers = [Modifier::Control, Modifier::Alt, Modifier::Shift]
+        .iter()
+        .copied()
+        .filter(|modifier| combo.matchers().contains(&KeyMatcher::Modifier(*modifier)))
+        .collect();
+
+    let mut keys: Vec<&KeyMatcher> = combo
+        .matchers()
+        .iter()
+        .filter(|matcher| !matches!(matcher, KeyMatcher::Modifier(_)))
+        .collect();
+    keys.sort_by_key(|key| match key {
+        KeyMatcher::Key(Keycode::LControl) | KeyMatcher::Key(Keycode::RControl) => 0,
+        KeyMatcher::Key(Keycode::LAlt) | KeyMatcher::Key(Keycode::RAlt) => 1,
+        KeyMatcher::Key(Keycode::LShift) | KeyMatcher::Key(Keycode::RShift) => 2,
+        KeyMatcher::Key(_) => 3,
+        KeyMatcher::Modifier(_) | KeyMatcher::Mouse(_) => 4,
+    });
+
+    (modifiers, keys)
+}
+
+fn modifier_name(modifier: Modifier, notation: Notation) -> String {
+    let name = match (notation, modifier) {
+        (Notation::Plus, Modifier::Control) => "Ctrl",
+        (Notation::Plus, Modifier::Alt) => "Alt",
+        (Notation::Plus, Modifier::Shift) => "Shift",
+        (Notation::Emacs, Modifier::Alt) => "M",
+        (Notation::Emacs, Modifier::Control) | (Notation::Vim, Modifier::Control) => "C",
+        (Notation::Emacs, Modifier::Shift) | (Notation::Vim, Modifier::Shift) => "S",
+        (Notation::Vim, Modifier::Alt) => "A",
+        (Notation::Mac, Modifier::Control) => "⌃",
+        (Notation::Mac, Modifier::Alt) => "⌥",
+        (Notation::Mac, Modifier::Shift) => "⇧",
+    };
+
+    name.to_string()
+}
+
+/// Names a modifier written in place of a key, as Emacs and Vim would read a lone `S` prefix as the S key.
+fn modifier_key_name(modifier: Modifier, notation: Notation) -> String {
+    let name = modifier_name(modifier, Notation::Plus);
+
+    match notation {
+        Notation::Emacs => format!("<{}>", name.to_lowercase()),
+        Notation::Vim => name,
+        Notation::Plus | Notation::Mac => modifier_name(modifier, notation),
+    }
+}
+
+fn matcher_name(matcher: &KeyMatcher, notation: Notation) -> String {
+    match matcher {
+        KeyMatcher::Key(key) => key_name(key, notation),
+        KeyMatcher::Modifier(modifier) => modifier_name(*modifier, notation),
+        KeyMatcher::Mouse(button) => button_name(*button, notation),
+    }
+}
+
+fn button_name(button: MouseButton, notation: Notation) -> String {
+    match (notation, button) {
+        (Notation::Emacs, _) => format!("<{}>", parse::button_name(button).to_lowercase()),
+        (Notation::Vim, MouseButton::Left) => "LeftMouse".to_string(),
+        (Notation::Vim, MouseButton::Middle) => "MiddleMouse".to_string(),
+        (Notation::Vim, MouseButton::Right) => "RightMouse".to_string(),
+        _ => parse::button_name(button).to_string(),
+    }
+}
+
+fn key_name(key: &Keycode, notation: Notation) -> String {
+    let name = parse::key_name(key);
+
+    match (notation, key) {
+        (Notation::Emacs, Keycode::Escape) => "ESC".to_string(),
+        (Notation::Emacs, Keycode::Space) => "SPC".to_string(),
+        (Notation::Emacs, Keycode::Enter) => "RET".to_string(),
+        (Notation::Emacs, _) if name.len() > 1 => format!("<{}>", name.to_lowercase()),
+        (Notation::Emacs, _) | (Notation::Vim, _) if name.len() == 1 => name.to_lowercase(),
+        (Notation::Vim, Keycode::Enter) => "CR".to_string(),
+        (Notation::Mac, Keycode::Escape) => "⎋".to_string(),
+        (Notation::Mac, Keycode::Enter) => "↩".to_string(),
+        _ => name.to_string(),
+    }
+}
+
+fn vim_modifier(token: &str) -> Option<Modifier> {
+    match token.to_ascii_uppercase().as_str() {
+        "C" => Some(Modifier::Control),
+        "A" | "M" => Some(Modifier::Alt),
+        "S" => Some(Modifier::Shift),
+        _ => None,
+    }
+}
+
+/// Parses `-` separated tokens where every token but the last may be a modifier prefix.
+fn parse_prefixed<M>(input: &str, offset: usize, modifier: M) -> Result<KeyCombo, ParseError>
+where
+    M: Fn(&str) -> Option<Modifier>,
+{
+    if input.trim().is_empty() {
+        return Err(ParseError::new(ParseErrorKind::Empty, "", offset));
+    }
+
+    let tokens: Vec<&str> = input.split('-').collect();
+    let mut matchers = Vec::new();
+    let mut start = offset;
+
+    for (index, token) in tokens.iter().enumerate() {
+        let trimmed = token.trim();
+        let position = start + (token.len() - token.trim_start().len());
+        start += token.len() + 1;
+
+        if trimmed.is_empty() {
+            return Err(ParseError::new(ParseErrorKind::MissingKey, "", position));
+        }
+
+        match modifier(trimmed) {
+            Some(modifier) if index + 1 < tokens.len() => matchers.push(modifier.into()),
+            _ => {
+                let name = trimmed
+                    .strip_prefix('<')
+                    .and_then(|name| name.strip_suffix('>'))
+                    .unwrap_or(trimmed);
+
+                matchers.push(parse::parse_key(name).map_err(|kind| ParseError::new(kind, trimmed, position))?);
+            }
+        }
+    }
+
+    Ok(KeyCombo::from(matchers))
+}
+
+fn parse_mac(input: &str) -> Result<KeyCombo, ParseError> {
+    let trimmed = input.trim_start();
+    let mut position = input.len() - trimmed.len();
+    let mut matchers: Vec<KeyMatcher> = Vec::new();
+
+    for symbol in trimmed.chars() {
+        let modifier = match symbol {
+            '⌃' => Modifier::Control,
+            '⌥' => Modifier::Alt,
+            '⇧' => Modifier::Shift,
+            '⌘' => {
+                return Err(ParseError::new(ParseErrorKind::UnsupportedKey, "⌘", position));
+            }
+            _ => break,
+        };
+
+        matchers.push(modifier.into());
+        position += symbol.len_utf8();
+    }
+
+    if input[position..].trim().is_empty() {
+        return match matchers.is_empty() {
+            true => Err(ParseError::new(ParseErrorKind::Empty, "", position)),
+            false => Ok(KeyCombo::from(matchers)),
+        };
+    }
+
+    let keys = parse::parse_combo(&input[position..], position)?;
+    matchers.extend(keys.matchers().iter().cloned());
+
+    Ok(KeyCombo::from(matchers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTATIONS: [Notation; 4] = [Notation::Plus, Notation::Emacs, Notation::Vim, Notation::Mac];
+
+    fn combo(input: &str) -> KeyCombo {
+        input.parse().unwrap()
+    }
+
+    fn display(input: &str, notation: Notation) -> String {
+        combo(input).display(notation).to_string()
+    }
+
+    #[test]
+    fn writes_plus_notation() {
+        assert_eq!(display("g+shift+ctrl", Notation::Plus), "Ctrl+Shift+G");
+        assert_eq!(display("Alt+Esc", Notation::Plus), "Alt+Esc");
+        assert_eq!(display("G+LCtrl", Notation::Plus), "LCtrl+G");
+    }
+
+    #[test]
+    fn writes_emacs_notation() {
+        assert_eq!(display("Ctrl+Shift+G", Notation::Emacs), "C-S-g");
+        assert_eq!(display("Alt+X", Notation::Emacs), "M-x");
+        assert_eq!(display("Ctrl+F5", Notation::Emacs), "C-<f5>");
+        assert_eq!(display("Ctrl+Space", Notation::Emacs), "C-SPC");
+    }
+
+    #[test]
+    fn writes_vim_notation() {
+        assert_eq!(display("Ctrl+Shift+G", Notation::Vim), "<C-S-g>");
+        assert_eq!(display("G", Notation::Vim), "g");
+        assert_eq!(display("Enter", Notation::Vim), "<CR>");
+        assert_eq!(display("Alt+Esc", Notation::Vim), "<A-Esc>");
+    }
+
+    #[test]
+    fn writes_lone_modifiers_as_keys() {
+        assert_eq!(display("Shift", Notation::Emacs), "<shift>");
+        assert_eq!(display("Ctrl+Shift", Notation::Emacs), "C-<shift>");
+        assert_eq!(display("Alt", Notation::Vim), "<Alt>");
+        assert_eq!(display("Ctrl+Shift", Notation::Vim), "<C-Shift>");
+        assert_eq!(display("Ctrl+Shift", Notation::Mac), "⌃⇧");
+    }
+
+    #[test]
+    fn writes_mac_notation() {
+        assert_eq!(display("Shift+Alt+Ctrl+G", Notation::Mac), "⌃⌥⇧G");
+        assert_eq!(display("Ctrl+Enter", Notation::Mac), "⌃↩");
+        assert_eq!(display("F1", Notation::Mac), "F1");
+    }
+
+    #[test]
+    fn writes_mouse_buttons() {
+        assert_eq!(display("MiddleClick+Ctrl", Notation::Plus), "Ctrl+MiddleClick");
+        assert_eq!(display("Ctrl+MiddleClick", Notation::Emacs), "C-<middleclick>");
+        assert_eq!(display("Ctrl+MiddleClick", Notation::Vim), "<C-MiddleMouse>");
+        assert_eq!(display("Mouse4+Mouse5", Notation::Mac), "Mouse4+Mouse5");
+    }
+
+    #[test]
+    fn round_trips_every_notation() {
+        let inputs = [
+            "Ctrl+Shift+G",
+            "Alt+F12",
+            "Esc",
+            "LCtrl+RShift+A",
+            "Ctrl+Enter",
+            "Shift+Space",
+            "Ctrl+G+H",
+            "Ctrl+LeftClick",
+            "Mouse4+Mouse5",
+            "Shift",
+            "Alt",
+            "Ctrl+Shift",
+            "Ctrl+Alt+Shift",
+            "Ctrl+LShift",
+        ];
+
+        for input in inputs.iter() {
+            for notation in NOTATIONS.iter() {
+                let written = display(input, *notation);
+
+                assert_eq!(KeyCombo::parse_notation(&written, *notation), Ok(combo(input)), "{}", written);
+            }
+        }
+    }
+
+    #[test]
+    fn parses_emacs_modifier_letters_only_before_the_key() {
+        assert_eq!(KeyCombo::parse_notation("C-s", Notation::Emacs), Ok(combo("Ctrl+S")));
+        assert_eq!(KeyCombo::parse_notation("S", Notation::Emacs), Ok(combo("S")));
+    }
+
+    #[test]
+    fn vim_modifiers_are_case_insensitive() {
+        assert_eq!(KeyCombo::parse_notation("<c-s-g>", Notation::Vim), Ok(combo("Ctrl+Shift+G")));
+    }
+
+    #[test]
+    fn reports_position_in_notation() {
+        let error = KeyCombo::parse_notation("<C-Foo>", Notation::Vim).unwrap_err();
+
+        assert_eq!(error.kind(), ParseErrorKind::UnknownKey);
+        assert_eq!(error.position(), 3);
+
+        let error = KeyCombo::parse_notation("⌃⌘G", Notation::Mac).unwrap_err();
+
+        assert_eq!(error.kind(), ParseErrorKind::UnsupportedKey);
+        assert_eq!(error.position(), "⌃".len());
+    }
+}
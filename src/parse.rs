@@ -0,0 +1,363 @@
+use crate::{KeyCombo, KeyMatcher, Modifier, MouseButton};
+use device_query::Keycode;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// The reason a [`ParseError`] occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// The input contains no key at all.
+    Empty,
+    /// A `+` is not surrounded by keys, e.g. `Ctrl++G` or `Ctrl+`.
+    MissingKey,
+    /// The token is not the name of any key.
+    UnknownKey,
+    /// The token names a key that can not be detected, e.g. `Super`.
+    UnsupportedKey,
+}
+
+/// Error returned when a keybind can not be parsed from a string.
+///
+/// # Example
+///
+/// ```
+/// use keybind::{KeyCombo, ParseErrorKind};
+///
+/// let error = "Ctrl+Shoft+G".parse::<KeyCombo>().unwrap_err();
+///
+/// assert_eq!(error.kind(), ParseErrorKind::UnknownKey);
+/// assert_eq!(error.token(), "Shoft");
+/// assert_eq!(error.position(), 5);
+/// assert_eq!(error.to_string(), "unknown key `Shoft` at position 5");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    token: String,
+    position: usize,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, token: &str, position: usize) -> ParseError {
+        ParseError {
+            kind,
+            token: token.to_string(),
+            position,
+        }
+    }
+
+    /// Returns the reason of the error.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the offending token, empty when a key is missing.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns the byte offset of the offending token in the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::Empty => write!(f, "no key provided"),
+            ParseErrorKind::MissingKey => write!(f, "missing key at position {}", self.position),
+            ParseErrorKind::UnknownKey => write!(f, "unknown key `{}` at position {}", self.token, self.position),
+            ParseErrorKind::UnsupportedKey => {
+                write!(f, "unsupported key `{}` at position {}", self.token, self.position)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses keybinds written as key names joined by `+`, e.g. `Ctrl+Shift+G`.
+///
+/// Key names are case-insensitive. `Ctrl`, `Shift` and `Alt` match either side of the keyboard, prefix them with
+/// `L` or `R` to match a single side. Aliases such as `Control`, `Esc` and `Return` are accepted. Mouse buttons are
+/// written `LeftClick`, `MiddleClick`, `RightClick`, `Mouse4` and `Mouse5`, see [`MouseButton`] for what the last
+/// two mean on each platform.
+///
+/// # Example
+///
+/// ```
+/// use keybind::{KeyCombo, KeyMatcher, Keycode, Modifier};
+///
+/// let combo: KeyCombo = "ctrl+shift+g".parse().unwrap();
+///
+/// assert_eq!(combo, KeyCombo::new(&[
+///     KeyMatcher::from(Modifier::Control),
+///     Modifier::Shift.into(),
+///     Keycode::G.into(),
+/// ]));
+/// ```
+impl FromStr for KeyCombo {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<KeyCombo, ParseError> {
+        parse_combo(input, 0)
+    }
+}
+
+/// Parses a combo found at byte `offset` of the whole input, so errors point at the right position.
+pub(crate) fn parse_combo(input: &str, offset: usize) -> Result<KeyCombo, ParseError> {
+    if input.trim().is_empty() {
+        return Err(ParseError::new(ParseErrorKind::Empty, "", offset));
+    }
+
+    let mut matchers = Vec::new();
+    let mut start = 0;
+
+    for token in input.split('+') {
+        let trimmed = token.trim();
+        let position = offset + start + (token.len() - token.trim_start().len());
+
+        if trimmed.is_empty() {
+            return Err(ParseError::new(ParseErrorKind::MissingKey, "", position));
+        }
+
+        matchers.push(parse_key(trimmed).map_err(|kind| ParseError::new(kind, trimmed, position))?);
+        start += token.len() + 1;
+    }
+
+    Ok(KeyCombo::from(matchers))
+}
+
+pub(crate) fn parse_key(name: &str) -> Result<KeyMatcher, ParseErrorKind> {
+    let name = name.to_ascii_lowercase();
+
+    let key = match name.as_str() {
+        "ctrl" | "control" => return Ok(Modifier::Control.into()),
+        "shift" => return Ok(Modifier::Shift.into()),
+        "alt" | "option" => return Ok(Modifier::Alt.into()),
+        "super" | "meta" | "win" | "windows" | "cmd" | "command" => return Err(ParseErrorKind::UnsupportedKey),
+        "leftclick" | "leftmouse" | "mouseleft" | "mouse1" => return Ok(MouseButton::Left.into()),
+        "middleclick" | "middlemouse" | "mousemiddle" | "mouse2" => return Ok(MouseButton::Middle.into()),
+        "rightclick" | "rightmouse" | "mouseright" | "mouse3" => return Ok(MouseButton::Right.into()),
+        "mouse4" | "button4" => return Ok(MouseButton::Button4.into()),
+        "mouse5" | "button5" => return Ok(MouseButton::Button5.into()),
+        "lctrl" | "lcontrol" => Keycode::LControl,
+        "rctrl" | "rcontrol" => Keycode::RControl,
+        "lshift" => Keycode::LShift,
+        "rshift" => Keycode::RShift,
+        "lalt" | "loption" => Keycode::LAlt,
+        "ralt" | "roption" | "altgr" => Keycode::RAlt,
+        "esc" | "escape" | "⎋" => Keycode::Escape,
+        "enter" | "return" | "ret" | "cr" | "↩" => Keycode::Enter,
+        "space" | "spc" => Keycode::Space,
+        "0" | "key0" => Keycode::Key0,
+        "1" | "key1" => Keycode::Key1,
+        "2" | "key2" => Keycode::Key2,
+        "3" | "key3" => Keycode::Key3,
+        "4" | "key4" => Keycode::Key4,
+        "5" | "key5" => Keycode::Key5,
+        "6" | "key6" => Keycode::Key6,
+        "7" | "key7" => Keycode::Key7,
+        "8" | "key8" => Keycode::Key8,
+        "9" | "key9" => Keycode::Key9,
+        "a" => Keycode::A,
+        "b" => Keycode::B,
+        "c" => Keycode::C,
+        "d" => Keycode::D,
+        "e" => Keycode::E,
+        "f" => Keycode::F,
+        "g" => Keycode::G,
+        "h" => Keycode::H,
+        "i" => Keycode::I,
+        "j" => Keycode::J,
+        "k" => Keycode::K,
+        "l" => Keycode::L,
+        "m" => Keycode::M,
+        "n" => Keycode::N,
+        "o" => Keycode::O,
+        "p" => Keycode::P,
+        "q" => Keycode::Q,
+        "r" => Keycode::R,
+        "s" => Keycode::S,
+        "t" => Keycode::T,
+        "u" => Keycode::U,
+        "v" => Keycode::V,
+        "w" => Keycode::W,
+        "x" => Keycode::X,
+        "y" => Keycode::Y,
+        "z" => Keycode::Z,
+        "f1" => Keycode::F1,
+        "f2" => Keycode::F2,
+        "f3" => Keycode::F3,
+        "f4" => Keycode::F4,
+        "f5" => Keycode::F5,
+        "f6" => Keycode::F6,
+        "f7" => Keycode::F7,
+        "f8" => Keycode::F8,
+        "f9" => Keycode::F9,
+        "f10" => Keycode::F10,
+        "f11" => Keycode::F11,
+        "f12" => Keycode::F12,
+        _ => return Err(ParseErrorKind::UnknownKey),
+    };
+
+    Ok(key.into())
+}
+
+/// Returns the name a mouse button is written with, which [`parse_key`] reads back.
+pub(crate) fn button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "LeftClick",
+        MouseButton::Middle => "MiddleClick",
+        MouseButton::Right => "RightClick",
+        MouseButton::Button4 => "Mouse4",
+        MouseButton::Button5 => "Mouse5",
+    }
+}
+
+/// Returns the name a key is written with, which [`parse_key`] reads back.
+pub(crate) fn key_name(key: &Keycode) -> &'static str {
+    match key {
+        Keycode::Key0 => "0",
+        Keycode::Key1 => "1",
+        Keycode::Key2 => "2",
+        Keycode::Key3 => "3",
+        Keycode::Key4 => "4",
+        Keycode::Key5 => "5",
+        Keycode::Key6 => "6",
+        Keycode::Key7 => "7",
+        Keycode::Key8 => "8",
+        Keycode::Key9 => "9",
+        Keycode::A => "A",
+        Keycode::B => "B",
+        Keycode::C => "C",
+        Keycode::D => "D",
+        Keycode::E => "E",
+        Keycode::F => "F",
+        Keycode::G => "G",
+        Keycode::H => "H",
+        Keycode::I => "I",
+        Keycode::J => "J",
+        Keycode::K => "K",
+        Keycode::L => "L",
+        Keycode::M => "M",
+        Keycode::N => "N",
+        Keycode::O => "O",
+        Keycode::P => "P",
+        Keycode::Q => "Q",
+        Keycode::R => "R",
+        Keycode::S => "S",
+        Keycode::T => "T",
+        Keycode::U => "U",
+        Keycode::V => "V",
+        Keycode::W => "W",
+        Keycode::X => "X",
+        Keycode::Y => "Y",
+        Keycode::Z => "Z",
+        Keycode::F1 => "F1",
+        Keycode::F2 => "F2",
+        Keycode::F3 => "F3",
+        Keycode::F4 => "F4",
+        Keycode::F5 => "F5",
+        Keycode::F6 => "F6",
+        Keycode::F7 => "F7",
+        Keycode::F8 => "F8",
+        Keycode::F9 => "F9",
+        Keycode::F10 => "F10",
+        Keycode::F11 => "F11",
+        Keycode::F12 => "F12",
+        Keycode::Escape => "Esc",
+        Keycode::Space => "Space",
+        Keycode::LControl => "LCtrl",
+        Keycode::RControl => "RCtrl",
+        Keycode::LShift => "LShift",
+        Keycode::RShift => "RShift",
+        Keycode::LAlt => "LAlt",
+        Keycode::RAlt => "RAlt",
+        Keycode::Enter => "Enter",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Result<KeyCombo, ParseError> {
+        input.parse()
+    }
+
+    #[test]
+    fn parses_modifiers_and_keys() {
+        let expected = KeyCombo::new(&[KeyMatcher::from(Modifier::Control), Modifier::Shift.into(), Keycode::G.into()]);
+
+        assert_eq!(parse("Ctrl+Shift+G"), Ok(expected));
+    }
+
+    #[test]
+    fn key_names_are_case_insensitive() {
+        assert_eq!(parse("CTRL+g"), parse("ctrl+G"));
+        assert_eq!(parse("eScApE"), Ok(KeyCombo::new(&[Keycode::Escape])));
+    }
+
+    #[test]
+    fn parses_aliases() {
+        assert_eq!(parse("Control+Return"), parse("Ctrl+Enter"));
+        assert_eq!(parse("Esc"), parse("Escape"));
+        assert_eq!(parse("Alt+1"), parse("Option+Key1"));
+    }
+
+    #[test]
+    fn parses_sided_modifiers() {
+        assert_eq!(parse("LCtrl+RShift+F5"), Ok(KeyCombo::new(&[Keycode::LControl, Keycode::RShift, Keycode::F5])));
+    }
+
+    #[test]
+    fn parses_mouse_buttons() {
+        let expected = KeyCombo::new(&[KeyMatcher::from(Modifier::Control), MouseButton::Middle.into()]);
+
+        assert_eq!(parse("Ctrl+MiddleClick"), Ok(expected));
+        assert_eq!(parse("mouse4+MOUSE5"), Ok(KeyCombo::new(&[MouseButton::Button4, MouseButton::Button5])));
+    }
+
+    #[test]
+    fn ignores_whitespace_around_keys() {
+        assert_eq!(parse(" Ctrl + G "), parse("Ctrl+G"));
+    }
+
+    #[test]
+    fn reports_unknown_key() {
+        let error = parse("Ctrl+Foo+G").unwrap_err();
+
+        assert_eq!(error.kind(), ParseErrorKind::UnknownKey);
+        assert_eq!(error.token(), "Foo");
+        assert_eq!(error.position(), 5);
+    }
+
+    #[test]
+    fn reports_unsupported_key() {
+        let error = parse("Super+L").unwrap_err();
+
+        assert_eq!(error.kind(), ParseErrorKind::UnsupportedKey);
+        assert_eq!(error.token(), "Super");
+        assert_eq!(error.position(), 0);
+    }
+
+    #[test]
+    fn reports_missing_key() {
+        assert_eq!(parse("Ctrl++G").unwrap_err(), ParseError::new(ParseErrorKind::MissingKey, "", 5));
+        assert_eq!(parse("Ctrl+").unwrap_err(), ParseError::new(ParseErrorKind::MissingKey, "", 5));
+        assert_eq!(parse("+G").unwrap_err(), ParseError::new(ParseErrorKind::MissingKey, "", 0));
+    }
+
+    #[test]
+    fn reports_empty_input() {
+        assert_eq!(parse("  ").unwrap_err().kind(), ParseErrorKind::Empty);
+    }
+
+    #[test]
+    fn position_skips_leading_whitespace() {
+        assert_eq!(parse("Ctrl +  Bar").unwrap_err().position(), 8);
+    }
+}
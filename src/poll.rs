@@ -0,0 +1,159 @@
+use crate::StopHandle;
+use std::cmp;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long `wait()` sleeps between two polls of the keyboard.
+///
+/// Polling more often lowers the latency between pressing a keybind and its callback running, at the cost of CPU
+/// time. While keys are held the `active` interval is used. While nothing is held the interval doubles after every
+/// poll until it reaches `idle`, since the first key of a keybind is usually a modifier and a little extra latency
+/// there goes unnoticed.
+///
+/// Only what is held at the moment of a poll is seen, so while idle a press shorter than `idle` can be missed
+/// entirely, e.g. quickly tapping Esc, an F-key or a modifier bound with [`Phase::Tap`](crate::Phase::Tap). Use
+/// [`PollInterval::fixed`] with a short interval for such keybinds.
+///
+/// The default polls every 10ms while keys are held and backs off to 50ms while idle.
+///
+/// # Example
+///
+/// ```ignore
+/// use keybind::{Keybind, Keycode, PollInterval};
+/// use std::time::Duration;
+///
+/// let mut keybind = Keybind::new(&[Keycode::LControl, Keycode::G]);
+///
+/// keybind.set_poll_interval(PollInterval::adaptive(Duration::from_millis(5), Duration::from_millis(100)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PollInterval {
+    active: Duration,
+    idle: Duration,
+}
+
+impl PollInterval {
+    /// Polls at the same interval whether keys are held or not.
+    pub fn fixed(interval: Duration) -> PollInterval {
+        PollInterval {
+            active: interval,
+            idle: interval,
+        }
+    }
+
+    /// Polls every `active` while keys are held, backing off up to `idle` while nothing is held.
+    pub fn adaptive(active: Duration, idle: Duration) -> PollInterval {
+        PollInterval {
+            active,
+            idle: cmp::max(active, idle),
+        }
+    }
+
+    /// Returns the interval used while keys are held.
+    pub fn active(&self) -> Duration {
+        self.active
+    }
+
+    /// Returns the longest interval used while nothing is held.
+    pub fn idle(&self) -> Duration {
+        self.idle
+    }
+
+    /// Returns how long to sleep after a poll, given the interval slept before it.
+    fn next(&self, previous: Duration, keys_held: bool) -> Duration {
+        if keys_held {
+            return self.active;
+        }
+
+        cmp::min(cmp::max(previous * 2, self.active), self.idle)
+    }
+}
+
+impl Default for PollInterval {
+    fn default() -> PollInterval {
+        PollInterval::adaptive(Duration::from_millis(10), Duration::from_millis(50))
+    }
+}
+
+impl From<Duration> for PollInterval {
+    fn from(interval: Duration) -> PollInterval {
+        PollInterval::fixed(interval)
+    }
+}
+
+/// Calls `poll` until the handle is stopped or the timeout elapses, returns bool if it was stopped.
+///
+/// `poll` returns bool if any key was held, which decides how long to sleep before the next poll.
+pub(crate) fn run<P>(stop: &StopHandle, interval: PollInterval, timeout: Option<Duration>, mut poll: P) -> bool
+where
+    P: FnMut() -> bool,
+{
+    // A timeout too long to be represented as an instant never elapses.
+    let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+    let mut sleep = interval.active;
+
+    loop {
+        if stop.take() {
+            return true;
+        }
+
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return false;
+        }
+
+        sleep = interval.next(sleep, poll());
+
+        match deadline {
+            Some(deadline) => thread::sleep(cmp::min(sleep, deadline.saturating_duration_since(Instant::now()))),
+            None => thread::sleep(sleep),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn backs_off_while_idle() {
+        let interval = PollInterval::adaptive(millis(10), millis(50));
+
+        assert_eq!(interval.next(millis(10), false), millis(20));
+        assert_eq!(interval.next(millis(20), false), millis(40));
+        assert_eq!(interval.next(millis(40), false), millis(50));
+        assert_eq!(interval.next(millis(50), false), millis(50));
+    }
+
+    #[test]
+    fn resets_when_keys_are_held() {
+        let interval = PollInterval::adaptive(millis(10), millis(50));
+
+        assert_eq!(interval.next(millis(50), true), millis(10));
+    }
+
+    #[test]
+    fn fixed_interval_never_backs_off() {
+        let interval = PollInterval::fixed(millis(10));
+
+        assert_eq!(interval.next(millis(10), false), millis(10));
+        assert_eq!(interval.next(millis(10), true), millis(10));
+    }
+
+    #[test]
+    fn zero_interval_stays_zero() {
+        let interval = PollInterval::fixed(Duration::ZERO);
+
+        assert_eq!(interval.next(Duration::ZERO, false), Duration::ZERO);
+    }
+
+    #[test]
+    fn idle_is_never_shorter_than_active() {
+        let interval = PollInterval::adaptive(millis(30), millis(10));
+
+        assert_eq!(interval.idle(), millis(30));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::parse::{self, ParseError, ParseErrorKind};
+use crate::{KeyCombo, KeyMatcher, Notation};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Combos that have to be pressed one after the other, e.g. Emacs' `Ctrl+X Ctrl+S`.
+///
+/// Each combo has to follow the previous one within the timeout, which defaults to one second. A single combo is a
+/// sequence of one step, so every [`KeyCombo`] converts into a `KeySequence`.
+///
+/// # Example
+///
+/// ```
+/// use keybind::KeySequence;
+/// use std::time::Duration;
+///
+/// let sequence: KeySequence = "Ctrl+X Ctrl+S".parse().unwrap();
+/// let sequence = sequence.with_timeout(Duration::from_millis(500));
+///
+/// assert_eq!(sequence.combos().len(), 2);
+/// assert_eq!(sequence.to_string(), "Ctrl+X Ctrl+S");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeySequence {
+    combos: Vec<KeyCombo>,
+    timeout: Duration,
+}
+
+impl KeySequence {
+    /// Constructs a new `KeySequence` from combos in the order they have to be pressed.
+    pub fn new<I: IntoIterator<Item = KeyCombo>>(combos: I) -> KeySequence {
+        KeySequence {
+            combos: combos.into_iter().collect(),
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    /// Sets how long to wait for the next combo before starting over.
+    pub fn with_timeout(mut self, timeout: Duration) -> KeySequence {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the combos in the order they have to be pressed.
+    pub fn combos(&self) -> &[KeyCombo] {
+        &self.combos
+    }
+
+    /// Returns how long to wait for the next combo before starting over.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns a value writing the sequence in provided notation, combos separated by spaces.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use keybind::{KeySequence, Notation};
+    ///
+    /// let sequence: KeySequence = "Ctrl+X Ctrl+S".parse().unwrap();
+    ///
+    /// assert_eq!(sequence.display(Notation::Emacs).to_string(), "C-x C-s");
+    /// ```
+    pub fn display(&self, notation: Notation) -> SequenceDisplay<'_> {
+        SequenceDisplay {
+            sequence: self,
+            notation,
+        }
+    }
+}
+
+/// Helper to write a [`KeySequence`] in a [`Notation`], returned by [`KeySequence::display`].
+#[derive(Debug, Clone, Copy)]
+pub struct SequenceDisplay<'a> {
+    sequence: &'a KeySequence,
+    notation: Notation,
+}
+
+impl fmt::Display for SequenceDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, combo) in self.sequence.combos.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+
+            combo.display(self.notation).fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display(Notation::Plus).fmt(f)
+    }
+}
+
+/// Parses combos separated by whitespace, each written as described on [`KeyCombo`].
+impl FromStr for KeySequence {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<KeySequence, ParseError> {
+        let steps = split_steps(input);
+
+        if steps.is_empty() {
+            return Err(ParseError::new(ParseErrorKind::Empty, "", 0));
+        }
+
+        let combos = steps
+            .into_iter()
+            .map(|(offset, step)| parse::parse_combo(step, offset))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(KeySequence::new(combos))
+    }
+}
+
+/// Splits the input at whitespace that is not next to a `+`, returning each step with its byte offset.
+fn split_steps(input: &str) -> Vec<(usize, &str)> {
+    let mut steps = Vec::new();
+    let mut start = None;
+    let mut previous = None;
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((index, character)) = chars.next() {
+        if !character.is_whitespace() {
+            start.get_or_insert(index);
+            previous = Some(character);
+            continue;
+        }
+
+        let next = input[index..].trim_start().chars().next();
+
+        if let Some(begin) = start {
+            if previous != Some('+') && next != Some('+') && next.is_some() {
+                steps.push((begin, &input[begin..index]));
+                start = None;
+            }
+        }
+
+        while chars.peek().is_some_and(|(_, character)| character.is_whitespace()) {
+            chars.next();
+        }
+    }
+
+    if let Some(begin) = start {
+        steps.push((begin, input[begin..].trim_end()));
+    }
+
+    steps
+}
+
+impl From<KeyCombo> for KeySequence {
+    fn from(combo: KeyCombo) -> KeySequence {
+        KeySequence::new(vec![combo])
+    }
+}
+
+impl From<Vec<KeyCombo>> for KeySequence {
+    fn from(combos: Vec<KeyCombo>) -> KeySequence {
+        KeySequence::new(combos)
+    }
+}
+
+impl<K: Into<KeyMatcher> + Clone> From<&[K]> for KeySequence {
+    fn from(keys: &[K]) -> KeySequence {
+        KeyCombo::new(keys).into()
+    }
+}
+
+impl<K: Into<KeyMatcher> + Clone, const N: usize> From<&[K; N]> for KeySequence {
+    fn from(keys: &[K; N]) -> KeySequence {
+        KeyCombo::new(keys).into()
+    }
+}
+
+impl<K: Into<KeyMatcher> + Clone> From<&Vec<K>> for KeySequence {
+    fn from(keys: &Vec<K>) -> KeySequence {
+        KeyCombo::new(keys).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use device_query::Keycode;
+
+    fn combo(input: &str) -> KeyCombo {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_steps_separated_by_whitespace() {
+        let sequence: KeySequence = "Ctrl+X   Ctrl+S".parse().unwrap();
+
+        assert_eq!(sequence.combos(), &[combo("Ctrl+X"), combo("Ctrl+S")]);
+    }
+
+    #[test]
+    fn whitespace_around_plus_does_not_split_steps() {
+        let sequence: KeySequence = " Ctrl + X Ctrl +S ".parse().unwrap();
+
+        assert_eq!(sequence.combos(), &[combo("Ctrl+X"), combo("Ctrl+S")]);
+    }
+
+    #[test]
+    fn single_combo_is_single_step() {
+        let sequence: KeySequence = "Ctrl+G".parse().unwrap();
+
+        assert_eq!(sequence, KeySequence::from(combo("Ctrl+G")));
+        assert_eq!(sequence.combos().len(), 1);
+    }
+
+    #[test]
+    fn keys_convert_to_single_step() {
+        let sequence = KeySequence::from(&[Keycode::LControl, Keycode::G]);
+
+        assert_eq!(sequence.combos(), &[KeyCombo::new(&[Keycode::G, Keycode::LControl])]);
+    }
+
+    #[test]
+    fn reports_position_in_whole_input() {
+        let error = "Ctrl+X Ctrl+Foo".parse::<KeySequence>().unwrap_err();
+
+        assert_eq!(error.kind(), ParseErrorKind::UnknownKey);
+        assert_eq!(error.position(), 12);
+    }
+
+    #[test]
+    fn reports_empty_input() {
+        assert_eq!("".parse::<KeySequence>().unwrap_err().kind(), ParseErrorKind::Empty);
+    }
+
+    #[test]
+    fn writes_steps_separated_by_space() {
+        let sequence: KeySequence = "ctrl+x ctrl+s".parse().unwrap();
+
+        assert_eq!(sequence.to_string(), "Ctrl+X Ctrl+S");
+        assert_eq!(sequence.display(Notation::Vim).to_string(), "<C-x> <C-s>");
+    }
+}
@@ -0,0 +1,66 @@
+use device_query::{DeviceQuery, DeviceState, Keycode, MouseState};
+
+/// Provides the keyboard state a [`Keybind`](crate::Keybind) is matched against.
+///
+/// [`DeviceState`] is the default implementation and queries the OS directly. Implement this trait to feed
+/// keys from any other backend.
+///
+/// # Example
+///
+/// ```ignore
+/// use keybind::{Keybind, KeySource, Keycode};
+///
+/// struct AlwaysPressed;
+///
+/// impl KeySource for AlwaysPressed {
+///     fn get_keys(&mut self) -> Vec<Keycode> {
+///         vec![Keycode::LControl, Keycode::G]
+///     }
+/// }
+///
+/// let mut keybind = Keybind::with_source(&[Keycode::LControl, Keycode::G], AlwaysPressed);
+/// ```
+pub trait KeySource {
+    /// Returns all keys that are currently pressed down.
+    fn get_keys(&mut self) -> Vec<Keycode>;
+
+    /// Returns the mouse position and the state of its buttons, polled right after [`get_keys`](KeySource::get_keys).
+    ///
+    /// Defaults to no button pressed, for sources without a mouse.
+    fn get_mouse(&mut self) -> MouseState {
+        MouseState {
+            coords: (0, 0),
+            button_pressed: Vec::new(),
+        }
+    }
+}
+
+impl KeySource for DeviceState {
+    fn get_keys(&mut self) -> Vec<Keycode> {
+        DeviceQuery::get_keys(self)
+    }
+
+    fn get_mouse(&mut self) -> MouseState {
+        DeviceQuery::get_mouse(self)
+    }
+}
+
+impl<S: KeySource + ?Sized> KeySource for &mut S {
+    fn get_keys(&mut self) -> Vec<Keycode> {
+        (**self).get_keys()
+    }
+
+    fn get_mouse(&mut self) -> MouseState {
+        (**self).get_mouse()
+    }
+}
+
+impl<S: KeySource + ?Sized> KeySource for Box<S> {
+    fn get_keys(&mut self) -> Vec<Keycode> {
+        (**self).get_keys()
+    }
+
+    fn get_mouse(&mut self) -> MouseState {
+        (**self).get_mouse()
+    }
+}
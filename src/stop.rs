@@ -0,0 +1,45 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Makes a running `wait()` loop return, from a callback or from any other thread.
+///
+/// Handles are cheap to clone and all clones control the same loop.
+///
+/// # Example
+///
+/// ```ignore
+/// use keybind::{Keybind, Keycode};
+///
+///fn main() {
+///    let mut keybind = Keybind::new(&[Keycode::Escape]);
+///    let stop = keybind.stop_handle();
+///
+///    keybind.on_trigger(move || {
+///        println!("Stopping after ESC was pressed");
+///        stop.stop();
+///    });
+///
+///    keybind.wait();
+///}
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StopHandle {
+    stopped: Arc<AtomicBool>,
+}
+
+impl StopHandle {
+    /// Requests the loop to return. If the loop is not running, the next one returns right away.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns bool if a stop has been requested and not yet handled by a loop.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Consumes a pending stop request, so the next loop runs again.
+    pub(crate) fn take(&self) -> bool {
+        self.stopped.swap(false, Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,126 @@
+use crate::TriggerEvent;
+use futures_channel::mpsc::UnboundedReceiver;
+use futures_core::Stream;
+use std::future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// An async [`Stream`] of the [`TriggerEvent`]s of a keybind polled on a background thread, see
+/// [`Keybind::stream`](crate::Keybind::stream).
+///
+/// Requires the `async` feature.
+///
+/// # Example
+///
+/// ```ignore
+/// use keybind::{Keybind, Keycode};
+///
+/// let mut triggers = Keybind::new(&[Keycode::LControl, Keycode::G]).stream();
+///
+/// while let Some(event) = triggers.next_trigger().await {
+///     println!("{} pressed", event.combo());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TriggerStream {
+    receiver: UnboundedReceiver<TriggerEvent>,
+}
+
+impl TriggerStream {
+    pub(crate) fn new(receiver: UnboundedReceiver<TriggerEvent>) -> TriggerStream {
+        TriggerStream { receiver }
+    }
+
+    /// Waits for the next trigger, returns `None` once the background thread stopped.
+    pub async fn next_trigger(&mut self) -> Option<TriggerEvent> {
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+impl Stream for TriggerStream {
+    type Item = TriggerEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<TriggerEvent>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.receiver.size_hint()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{KeySource, Keybind, Keycode, MockKeySource};
+    use std::future::Future;
+    use std::sync::mpsc::{self, RecvTimeoutError};
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+    use std::thread;
+    use std::time::Duration;
+
+    /// Does nothing when woken, as the futures of the tests are polled in a loop.
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    pub(crate) fn noop_waker() -> Waker {
+        Waker::from(Arc::new(NoopWaker))
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::yield_now(),
+            }
+        }
+    }
+
+    #[test]
+    fn yields_every_trigger() {
+        let source = || {
+            MockKeySource::new()
+                .press(&[Keycode::LControl, Keycode::G])
+                .release(&[Keycode::G])
+                .press(&[Keycode::G])
+        };
+        let keybind = Keybind::with_source(&[Keycode::LControl, Keycode::G], MockKeySource::new());
+        let stop = keybind.stop_handle();
+        let mut triggers = keybind.stream_with(source);
+
+        assert!(block_on(triggers.next_trigger()).is_some());
+        assert!(block_on(triggers.next_trigger()).is_some());
+        stop.stop();
+        assert!(block_on(triggers.next_trigger()).is_none());
+    }
+
+    /// A source without keys, whose sender disconnects once the thread polling it drops it.
+    struct Watched {
+        _alive: mpsc::Sender<()>,
+    }
+
+    impl KeySource for Watched {
+        fn get_keys(&mut self) -> Vec<Keycode> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn stops_polling_once_dropped() {
+        let (sender, receiver) = mpsc::channel();
+        let keybind = Keybind::with_source(&[Keycode::LControl, Keycode::G], MockKeySource::new());
+        let triggers = keybind.stream_with(move || Watched { _alive: sender });
+
+        drop(triggers);
+
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Err(RecvTimeoutError::Disconnected));
+    }
+}
@@ -0,0 +1,266 @@
+use crate::TriggerEvent;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A running async handler, handed to the spawner of [`Keybind::on_trigger_async`](crate::Keybind::on_trigger_async).
+///
+/// Requires the `async` feature.
+pub type HandlerFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// What happens when an async handler triggers again while a previous run is still going.
+///
+/// Requires the `async` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Concurrency {
+    /// Spawns a handler for every trigger, next to the ones still running.
+    #[default]
+    Concurrent,
+    /// Runs the handlers one after the other, in the order they were triggered.
+    Queue,
+    /// Ignores triggers while a handler is still running.
+    Drop,
+}
+
+#[derive(Default)]
+struct Handlers {
+    running: bool,
+    queued: VecDeque<HandlerFuture>,
+}
+
+#[derive(Clone, Default)]
+struct SharedHandlers(Arc<Mutex<Handlers>>);
+
+impl SharedHandlers {
+    fn lock(&self) -> MutexGuard<'_, Handlers> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Marks the handlers as no longer running once the future running them gets dropped by the runtime before
+/// finishing, so later triggers are not ignored or queued forever.
+struct Running {
+    handlers: SharedHandlers,
+    finished: bool,
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        if !self.finished {
+            let mut handlers = self.handlers.lock();
+            handlers.running = false;
+            handlers.queued.clear();
+        }
+    }
+}
+
+/// Returns a trigger callback that runs provided async handler through provided spawner, following the policy.
+pub(crate) fn async_callback<S, C, F>(
+    concurrency: Concurrency,
+    mut spawn: S,
+    mut handler: C,
+) -> impl FnMut(&TriggerEvent)
+where
+    S: FnMut(HandlerFuture),
+    C: FnMut(TriggerEvent) -> F,
+    F: Future<Output = ()> + Send + 'static,
+{
+    let handlers = SharedHandlers::default();
+
+    move |event| {
+        if concurrency == Concurrency::Concurrent {
+            spawn(Box::pin(handler(event.clone())));
+            return;
+        }
+
+        let mut state = handlers.lock();
+
+        if state.running {
+            if concurrency == Concurrency::Queue {
+                state.queued.push_back(Box::pin(handler(event.clone())));
+            }
+
+            return;
+        }
+
+        state.running = true;
+        drop(state);
+
+        spawn(Box::pin(run(Box::pin(handler(event.clone())), handlers.clone())));
+    }
+}
+
+/// Runs provided handler, then the ones queued in the meantime.
+fn run(first: HandlerFuture, handlers: SharedHandlers) -> impl Future<Output = ()> + Send {
+    // Created outside of the async block, so it also gets dropped when the runtime never polls the future.
+    let mut running = Running { handlers, finished: false };
+
+    async move {
+        let mut next = Some(first);
+
+        while let Some(handler) = next {
+            handler.await;
+
+            let mut state = running.handlers.lock();
+            next = state.queued.pop_front();
+
+            if next.is_none() {
+                state.running = false;
+                running.finished = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding::Binding;
+    use crate::input::Snapshot;
+    use crate::stream::tests::noop_waker;
+    use crate::Keycode;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::task::{Context, Poll};
+    use std::time::Instant;
+
+    /// A future pending until opened.
+    #[derive(Clone, Default)]
+    struct Gate(Arc<AtomicBool>);
+
+    impl Gate {
+        fn open(&self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    impl Future for Gate {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+            if self.0.load(Ordering::SeqCst) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Spawns handlers onto a list polled by hand, standing in for an async runtime.
+    #[derive(Clone, Default)]
+    struct Runtime(Rc<RefCell<Vec<HandlerFuture>>>);
+
+    impl Runtime {
+        fn spawner(&self) -> impl FnMut(HandlerFuture) {
+            let tasks = self.0.clone();
+
+            move |future| tasks.borrow_mut().push(future)
+        }
+
+        fn tasks(&self) -> usize {
+            self.0.borrow().len()
+        }
+
+        fn run_until_stalled(&self) {
+            let waker = noop_waker();
+            let mut cx = Context::from_waker(&waker);
+
+            self.0.borrow_mut().retain_mut(|task| task.as_mut().poll(&mut cx).is_pending());
+        }
+    }
+
+    fn event() -> TriggerEvent {
+        let snapshot = Snapshot {
+            inputs: Vec::new(),
+            position: (0, 0),
+        };
+
+        TriggerEvent::new(&Binding::new((&[Keycode::A]).into()), &snapshot, Instant::now(), 0)
+    }
+
+    /// Returns a callback whose handlers wait for the gate, then log their number.
+    fn gated(concurrency: Concurrency, runtime: &Runtime) -> (impl FnMut(&TriggerEvent), Gate, Arc<Mutex<Vec<u32>>>) {
+        let gate = Gate::default();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (handler_gate, handler_log) = (gate.clone(), log.clone());
+        let mut count = 0;
+
+        let callback = async_callback(concurrency, runtime.spawner(), move |_| {
+            count += 1;
+            let (gate, log, number) = (handler_gate.clone(), handler_log.clone(), count);
+
+            async move {
+                gate.await;
+                log.lock().unwrap().push(number);
+            }
+        });
+
+        (callback, gate, log)
+    }
+
+    #[test]
+    fn concurrent_spawns_every_trigger() {
+        let runtime = Runtime::default();
+        let (mut callback, gate, log) = gated(Concurrency::Concurrent, &runtime);
+
+        callback(&event());
+        callback(&event());
+        runtime.run_until_stalled();
+        assert_eq!(runtime.tasks(), 2);
+
+        gate.open();
+        runtime.run_until_stalled();
+        assert_eq!(*log.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn queue_runs_triggers_one_after_the_other() {
+        let runtime = Runtime::default();
+        let (mut callback, gate, log) = gated(Concurrency::Queue, &runtime);
+
+        callback(&event());
+        callback(&event());
+        runtime.run_until_stalled();
+        assert_eq!(runtime.tasks(), 1);
+
+        gate.open();
+        runtime.run_until_stalled();
+        assert_eq!(*log.lock().unwrap(), vec![1, 2]);
+        assert_eq!(runtime.tasks(), 0);
+
+        callback(&event());
+        runtime.run_until_stalled();
+        assert_eq!(*log.lock().unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn drop_ignores_triggers_while_running() {
+        let runtime = Runtime::default();
+        let (mut callback, gate, log) = gated(Concurrency::Drop, &runtime);
+
+        callback(&event());
+        callback(&event());
+        gate.open();
+        runtime.run_until_stalled();
+        callback(&event());
+        runtime.run_until_stalled();
+
+        assert_eq!(*log.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn cancelled_handler_does_not_block_later_triggers() {
+        let runtime = Runtime::default();
+        let (mut callback, gate, log) = gated(Concurrency::Drop, &runtime);
+
+        callback(&event());
+        runtime.0.borrow_mut().clear();
+        gate.open();
+        callback(&event());
+        runtime.run_until_stalled();
+
+        assert_eq!(*log.lock().unwrap(), vec![2]);
+    }
+}